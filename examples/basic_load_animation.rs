@@ -7,9 +7,9 @@ fn main() {
         .add_plugins(DefaultPlugins.set(ImagePlugin {
             default_sampler: bevy::render::texture::ImageSampler::nearest_descriptor(),
         }))
-        // add the plugin to our game, the 10 is the max number of nodes in a single chain
+        // add the plugin to our game, by default 10 is the max number of nodes in a single chain
         // this provents the app getting stuck in a loop
-        .add_plugins(SpriteAnimationPlugin::<10>)
+        .add_plugins(SpriteAnimationPlugin::default())
         .add_systems(Startup, setup_animations)
         // register the Match node with our games Generic matching vairiable
         .register_type::<MatchNode<ZState>>()
@@ -13,14 +13,9 @@ mod animation {
 
     impl Plugin for YourAnimationPlugin {
         fn build(&self, app: &mut App) {
-            app.add_systems(
-                Update,
-                (
-                    zombie_state_update.before(AnimationSet::Update),
-                    zombie_update_state.after(AnimationSet::Update),
-                ),
-            )
-            .register_type::<ZState>();
+            app.add_plugins(AttributeBinding::<ZState>::new("ZombieState"))
+                .add_systems(Update, zombie_events.after(AnimationSet::PostUpdate))
+                .register_type::<ZState>();
         }
     }
 
@@ -39,7 +34,7 @@ mod animation {
         PartialOrd,
         Ord,
     )]
-    #[reflect_value(Deserialize)]
+    #[reflect_value(PartialEq, Serialize, Deserialize, AnimationStateObj)]
     pub enum ZState {
         Idle,
         Walking,
@@ -60,32 +55,20 @@ mod animation {
         }
     }
 
-    ///updates the zombies animation state to reflect its progam state
-    fn zombie_state_update(
-        mut zombies: Query<(&mut AnimationState, &ZState), (With<super::Zombie>, Changed<ZState>)>,
-    ) {
-        let att = Attribute::new_attribute("ZombieState");
-        for (mut state, name) in zombies.iter_mut() {
-            state.set_attribute(att.clone(), *name);
-        }
-    }
-
-    ///update the zombies program state is it was changed by the animation
-    fn zombie_update_state(
-        mut zombies: Query<(&AnimationState, &mut ZState), With<super::Zombie>>,
-    ) {
-        let attribute = Attribute::from_str("ZombieState");
-        for (state, mut name) in zombies.iter_mut() {
-            if state.changed(&attribute) {
-                *name = *state.attribute::<ZState>(&attribute);
-            }
+    ///react to the markers put on frames in the .nodetree
+    fn zombie_events(mut events: EventReader<AnimationEvent>) {
+        for event in events.iter() {
+            info!(
+                "{:?} reached {} on frame {}",
+                event.entity, event.marker, event.frame
+            );
         }
     }
 }
 
 mod player {
     use bevy::prelude::*;
-    use bevy_sprite_animation::{prelude::Attribute, state::AnimationState};
+    use bevy_sprite_animation::prelude::{core_attribute, AnimationState, TypedAttribute};
 
     use super::animation::ZState;
 
@@ -100,14 +83,10 @@ mod player {
 
     fn player_animation_update(
         mut player: Query<(&mut ZState, &mut AnimationState), With<Player>>,
-        mut local: Local<[Attribute; 3]>,
         input: Res<Input<KeyCode>>,
     ) {
-        if local[0] == Attribute::Default {
-            local[0] = Attribute::new_attribute("ZombieState");
-            local[1] = Attribute::new_index("Stand");
-            local[2] = Attribute::new_index("Fall");
-        }
+        let stand = TypedAttribute::new_index("Stand");
+        let fall = TypedAttribute::new_index("Fall");
         let (mut zstate, mut animation) = player.single_mut();
         for key in input.get_just_pressed() {
             match (key, *zstate) {
@@ -116,7 +95,7 @@ mod player {
                 | (KeyCode::A, ZState::Idle)
                 | (KeyCode::Left, ZState::Idle) => {
                     if zstate.as_ref() == &ZState::Walking || zstate.as_ref() == &ZState::Idle {
-                        animation.set_attribute(Attribute::FlipX, true);
+                        animation.set(&core_attribute::FLIP_X, true);
                     }
                 }
                 (KeyCode::D, ZState::Walking)
@@ -124,7 +103,7 @@ mod player {
                 | (KeyCode::D, ZState::Idle)
                 | (KeyCode::Right, ZState::Idle) => {
                     if zstate.as_ref() == &ZState::Walking || zstate.as_ref() == &ZState::Idle {
-                        animation.set_attribute(Attribute::FlipX, false);
+                        animation.set(&core_attribute::FLIP_X, false);
                     }
                 }
                 (KeyCode::Up, state) => {
@@ -143,7 +122,10 @@ mod player {
                     }
                 }
                 (KeyCode::Down, state) => {
-                    let facing = *animation.attribute::<bool>(&Attribute::FlipX);
+                    let facing = animation
+                        .get(&core_attribute::FLIP_X)
+                        .cloned()
+                        .unwrap_or_default();
                     *zstate = match state {
                         ZState::Idle => {
                             if facing {
@@ -157,14 +139,14 @@ mod player {
                         ZState::Attacking => ZState::Attacking,
                         ZState::FallF => ZState::FallF,
                         ZState::StandF => {
-                            let index = animation.index(&local[1]);
-                            animation.set_attribute(local[2].clone(), 6 - index);
+                            let index = animation.get(&stand).cloned().unwrap_or_default();
+                            animation.set(&fall, 6 - index);
                             ZState::FallF
                         }
                         ZState::FallB => ZState::FallB,
                         ZState::StandB => {
-                            let index = animation.index(&local[1]);
-                            animation.set_attribute(local[2].clone(), 7 - index);
+                            let index = animation.get(&stand).cloned().unwrap_or_default();
+                            animation.set(&fall, 7 - index);
                             ZState::FallB
                         }
                         ZState::Test => ZState::Test,
@@ -196,7 +178,7 @@ fn main() {
     //add this plugin
     app.add_plugins((
         animation::YourAnimationPlugin,
-        SpriteAnimationPlugin::<20>,
+        SpriteAnimationPlugin { max_depth: 20 },
         player::Player,
     ))
     .add_systems(Startup, setup_animations)
@@ -239,17 +221,9 @@ fn print_tree(
 fn setup_animations(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(Camera2dBundle::default());
 
-    let fall_index = Attribute::new_index("Fall");
-    let stand_index = Attribute::new_index("Stand");
-    let attack_index = Attribute::new_index("Attack");
-
-    let test_handle: Handle<AnimationNode> = asset_server.load("test.node");
     let tree_handle: Handle<AnimationNode> = asset_server.load("./Zombie1.nodetree");
 
-    let mut start = AnimationState::default();
-    start.set_temporary(fall_index);
-    start.set_temporary(stand_index);
-    start.set_temporary(attack_index);
+    //the start node and starting attributes are set in the .animator file
     commands.spawn((
         SpriteBundle {
             transform: Transform::from_translation(Vec3::X * 10.),
@@ -261,9 +235,8 @@ fn setup_animations(mut commands: Commands, asset_server: Res<AssetServer>) {
         },
         Zombie,
         ZState::Attacking,
-        start,
+        AnimatorBundle::new(asset_server.load("Zombie1.animator")),
         player::Player,
-        StartNode::from_handle(test_handle),
     ));
 
     commands.insert_resource(Handles(vec![tree_handle]));
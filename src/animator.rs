@@ -0,0 +1,422 @@
+use bevy::asset::HandleId;
+use bevy::prelude::*;
+use bevy::reflect::{TypePath, TypeUuid};
+use bevy::utils::HashMap;
+
+use crate::prelude::*;
+use crate::state::AnimationStateObj;
+
+/// The start node, starting attributes and temporary attributes for an animated entity
+/// this is loaded from `.animator` files so they can be changed without recompiling
+/// ```ron
+/// AnimatorPreset(
+///     // node files that are loaded with the preset
+///     nodes: ["test.node", "Zombie1.nodetree"],
+///     start: Id(0),
+///     // each value is written as {"type::path": value}
+///     attributes: {
+///         FlipX: {"bool": true},
+///         Custom("ZombieState"): {"zombie::animation::ZState": Attacking},
+///     },
+///     temporary: [Index("Fall"), Index("Stand")],
+///     lifetimes: {Custom("Invulnerable"): Seconds(0.3)},
+///     timers: [(timer: Custom("SinceHit"), watched: Some(Custom("Hit")))],
+/// )
+/// ```
+/// when the preset file changes, entities using it get the new start node, temporary attributes and lifetimes
+/// and attributes that were taken out of `temporary` or `lifetimes` become persistent again,
+/// the initial attributes and timers are only applied when the `AnimationState` is added
+/// so changing them does not reset the state of entities that are already animating
+#[derive(Debug, TypeUuid, TypePath)]
+#[uuid = "86e75629-94d8-499c-8fd4-92578246e207"]
+pub struct AnimatorPreset {
+    pub start: NodeId<'static>,
+    pub attributes: Vec<(Attribute, Box<dyn AnimationStateObj>)>,
+    pub temporary: Vec<Attribute>,
+    pub lifetimes: Vec<(Attribute, Lifetime)>,
+    pub timers: Vec<AttributeTimer>,
+    nodes: Vec<Handle<AnimationNode>>,
+}
+
+impl AnimatorPreset {
+    pub fn new(start: impl Into<NodeId<'static>>) -> AnimatorPreset {
+        AnimatorPreset {
+            start: start.into(),
+            attributes: Vec::new(),
+            temporary: Vec::new(),
+            lifetimes: Vec::new(),
+            timers: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn with_attribute<D: AnimationStateObj>(
+        mut self,
+        key: Attribute,
+        val: D,
+    ) -> AnimatorPreset {
+        self.attributes.push((key, Box::new(val)));
+        self
+    }
+
+    pub fn with_temporary(mut self, key: Attribute) -> AnimatorPreset {
+        self.temporary.push(key);
+        self
+    }
+
+    pub fn with_lifetime(mut self, key: Attribute, lifetime: Lifetime) -> AnimatorPreset {
+        self.lifetimes.push((key, lifetime));
+        self
+    }
+
+    pub fn with_timer(mut self, timer: AttributeTimer) -> AnimatorPreset {
+        self.timers.push(timer);
+        self
+    }
+
+    /// the node files loaded with this preset
+    pub fn nodes(&self) -> &[Handle<AnimationNode>] {
+        &self.nodes
+    }
+
+    /// a new [`AnimationState`] with the attributes of this preset
+    pub fn state(&self) -> AnimationState {
+        let mut state = AnimationState::default();
+        for (key, val) in self.attributes.iter() {
+            state.set_boxed(key.clone(), val.clone_obj());
+        }
+        self.apply_lifetimes(&mut state);
+        for timer in self.timers.iter() {
+            state.add_timer(timer.clone());
+        }
+        state
+    }
+
+    /// sets the temporary attributes and lifetimes of this preset on `state`,
+    /// attributes that already have the same lifetime keep how long they have been idle
+    pub fn apply_lifetimes(&self, state: &mut AnimationState) {
+        let temporary = self.temporary.iter().map(|key| (key, Lifetime::FRAME));
+        let lifetimes = self
+            .lifetimes
+            .iter()
+            .map(|(key, lifetime)| (key, *lifetime));
+        for (key, lifetime) in temporary.chain(lifetimes) {
+            if state.lifetime(key) != Some(lifetime) {
+                state.set_lifetime(key.clone(), lifetime);
+            }
+        }
+    }
+
+    /// the attributes this preset makes temporary or gives a lifetime
+    pub fn timed_attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.temporary
+            .iter()
+            .chain(self.lifetimes.iter().map(|(key, _)| key))
+    }
+
+    /// applies the lifetimes of this preset to a `state` that had the lifetimes of `previous`,
+    /// attributes in `previous` this preset no longer has are made persistent again
+    pub fn reapply_lifetimes(&self, previous: &[Attribute], state: &mut AnimationState) {
+        for key in previous {
+            if !self.timed_attributes().any(|timed| timed == key) {
+                state.set_persistent(key);
+            }
+        }
+        self.apply_lifetimes(state);
+    }
+
+    pub fn start_node(&self) -> StartNode {
+        StartNode(self.start.clone())
+    }
+}
+
+/// Adds an [`AnimationState`] and [`StartNode`] to the entity once the preset is loaded
+/// entities that already have an `AnimationState` are left as they are
+/// when the preset file is changed the `StartNode`, temporary attributes and lifetimes of every entity using it are updated
+#[derive(Bundle, Default)]
+pub struct AnimatorBundle {
+    pub preset: Handle<AnimatorPreset>,
+}
+
+impl AnimatorBundle {
+    pub fn new(preset: Handle<AnimatorPreset>) -> AnimatorBundle {
+        AnimatorBundle { preset }
+    }
+}
+
+pub(crate) fn apply_animator_presets(
+    mut commands: Commands,
+    presets: Res<Assets<AnimatorPreset>>,
+    mut events: EventReader<AssetEvent<AnimatorPreset>>,
+    new: Query<(Entity, &Handle<AnimatorPreset>), Without<AnimationState>>,
+    mut existing: Query<(&Handle<AnimatorPreset>, &mut StartNode, &mut AnimationState)>,
+    mut applied: Local<HashMap<HandleId, Vec<Attribute>>>,
+) {
+    for (entity, handle) in new.iter() {
+        let Some(preset) = presets.get(handle) else {continue;};
+        applied
+            .entry(handle.id())
+            .or_insert_with(|| preset.timed_attributes().cloned().collect());
+        commands
+            .entity(entity)
+            .insert((preset.state(), preset.start_node()));
+    }
+    for event in events.iter() {
+        let handle = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { handle } => {
+                applied.remove(&handle.id());
+                continue;
+            }
+        };
+        let Some(preset) = presets.get(handle) else {continue;};
+        let previous = applied.insert(handle.id(), preset.timed_attributes().cloned().collect());
+        let AssetEvent::Modified { .. } = event else {continue;};
+        let previous = previous.unwrap_or_default();
+        for (_, mut start, mut state) in
+            existing.iter_mut().filter(|(preset, ..)| *preset == handle)
+        {
+            if start.0 != preset.start {
+                start.0 = preset.start.clone();
+            }
+            preset.reapply_lifetimes(&previous, &mut state);
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+pub(crate) use loader::AnimatorLoader;
+
+#[cfg(feature = "serialize")]
+mod loader {
+    use bevy::asset::{AssetLoader, AssetPath, LoadedAsset};
+    use bevy::prelude::*;
+    use bevy::reflect::TypeRegistryInternal;
+
+    use super::AnimatorPreset;
+    use crate::prelude::*;
+    use crate::state::StateDataSeed;
+
+    pub(crate) struct AnimatorLoader(pub AppTypeRegistry);
+
+    impl AssetLoader for AnimatorLoader {
+        fn extensions(&self) -> &[&str] {
+            &["animator"]
+        }
+
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            load_context: &'a mut bevy::asset::LoadContext,
+        ) -> bevy::utils::BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+            Box::pin(async move {
+                let registry = self.0.read();
+                let mut deserializer = ron::Deserializer::from_bytes(bytes)?;
+                let (mut preset, paths) = serde::de::DeserializeSeed::deserialize(
+                    PresetSeed {
+                        registry: &registry,
+                    },
+                    &mut deserializer,
+                )?;
+                let paths = paths
+                    .into_iter()
+                    .map(|path| AssetPath::from(path).to_owned())
+                    .collect::<Vec<_>>();
+                preset.nodes = paths
+                    .iter()
+                    .map(|path| load_context.get_handle(path.clone()))
+                    .collect();
+                load_context.set_default_asset(LoadedAsset::new(preset).with_dependencies(paths));
+                Ok(())
+            })
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(field_identifier, rename_all = "lowercase")]
+    enum Fileds {
+        Nodes,
+        Start,
+        Attributes,
+        Temporary,
+        Lifetimes,
+        Timers,
+    }
+
+    struct PresetSeed<'a> {
+        registry: &'a TypeRegistryInternal,
+    }
+
+    impl<'a, 'de> serde::de::DeserializeSeed<'de> for PresetSeed<'a> {
+        type Value = (AnimatorPreset, Vec<String>);
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_struct(
+                "AnimatorPreset",
+                &[
+                    "nodes",
+                    "start",
+                    "attributes",
+                    "temporary",
+                    "lifetimes",
+                    "timers",
+                ],
+                self,
+            )
+        }
+    }
+
+    impl<'a, 'de> serde::de::Visitor<'de> for PresetSeed<'a> {
+        type Value = (AnimatorPreset, Vec<String>);
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("AnimatorPreset with a start node")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut start = None;
+            let mut nodes = Vec::new();
+            let mut attributes = Vec::new();
+            let mut temporary = Vec::new();
+            let mut lifetimes = Vec::new();
+            let mut timers = Vec::new();
+            while let Some(key) = map.next_key::<Fileds>()? {
+                match key {
+                    Fileds::Nodes => nodes = map.next_value()?,
+                    Fileds::Start => start = Some(map.next_value::<NodeId<'static>>()?),
+                    Fileds::Attributes => {
+                        attributes = map
+                            .next_value_seed(StateDataSeed::new(self.registry))?
+                            .into_iter()
+                            .collect()
+                    }
+                    Fileds::Temporary => temporary = map.next_value()?,
+                    Fileds::Lifetimes => {
+                        lifetimes = map
+                            .next_value::<std::collections::BTreeMap<Attribute, Lifetime>>()?
+                            .into_iter()
+                            .collect()
+                    }
+                    Fileds::Timers => timers = map.next_value()?,
+                }
+            }
+            let Some(start) = start else {return Err(serde::de::Error::missing_field("start"));};
+            let mut preset = AnimatorPreset::new(start);
+            preset.attributes = attributes;
+            preset.temporary = temporary;
+            preset.lifetimes = lifetimes;
+            preset.timers = timers;
+            Ok((preset, nodes))
+        }
+    }
+
+    #[test]
+    fn preset_ron() {
+        let registry = AppTypeRegistry::default();
+        crate::state::register_state_type::<bool>(&mut registry.write());
+        let registry = registry.read();
+        let mut deserializer = ron::Deserializer::from_str(
+            r#"AnimatorPreset(
+                nodes: ["test.node"],
+                start: Id(0),
+                attributes: {FlipX: {"bool": true}},
+                temporary: [Index("Fall")],
+                lifetimes: {Custom("Flash"): Seconds(0.3)},
+                timers: [(timer: Custom("SinceHit"), watched: Some(Custom("Hit")))],
+            )"#,
+        )
+        .unwrap();
+        let (preset, nodes) = serde::de::DeserializeSeed::deserialize(
+            PresetSeed {
+                registry: &registry,
+            },
+            &mut deserializer,
+        )
+        .unwrap();
+        assert_eq!(nodes, vec!["test.node".to_string()]);
+        assert_eq!(preset.start, NodeId::U64(0));
+        assert_eq!(preset.temporary, vec![Attribute::new_index("Fall")]);
+        let state = preset.state();
+        assert!(*state.attribute::<bool>(&Attribute::FlipX));
+        assert_eq!(
+            state.lifetime(&Attribute::new_attribute("Flash")),
+            Some(Lifetime::Seconds(0.3))
+        );
+        assert_eq!(
+            *state.attribute::<f32>(&Attribute::new_attribute("SinceHit")),
+            0.0
+        );
+    }
+}
+
+#[test]
+fn preset_state() {
+    let preset = AnimatorPreset::new(NodeId::from_name("start"))
+        .with_attribute(Attribute::FlipX, true)
+        .with_attribute(Attribute::new_attribute("Speed"), 2.0f32)
+        .with_temporary(Attribute::new_index("Fall"));
+    let state = preset.state();
+    assert!(*state.attribute::<bool>(&Attribute::FlipX));
+    assert_eq!(
+        *state.attribute::<f32>(&Attribute::new_attribute("Speed")),
+        2.0
+    );
+    assert!(state.is_temporary(&Attribute::new_index("Fall")));
+    assert_eq!(preset.start_node().0, NodeId::from_name("start"));
+
+    let mut state = state;
+    state.set_attribute(Attribute::new_attribute("Speed"), 5.0f32);
+    let modified = AnimatorPreset::new(NodeId::from_name("start"))
+        .with_attribute(Attribute::new_attribute("Speed"), 2.0f32)
+        .with_lifetime(Attribute::new_index("Fall"), Lifetime::Seconds(0.5))
+        .with_temporary(Attribute::new_attribute("Hit"));
+    modified.apply_lifetimes(&mut state);
+    assert_eq!(
+        state.lifetime(&Attribute::new_index("Fall")),
+        Some(Lifetime::Seconds(0.5))
+    );
+    assert!(state.is_temporary(&Attribute::new_attribute("Hit")));
+    assert_eq!(
+        *state.attribute::<f32>(&Attribute::new_attribute("Speed")),
+        5.0
+    );
+}
+
+#[test]
+fn preset_reload_drops_lifetimes() {
+    let fall = Attribute::new_index("Fall");
+    let flash = Attribute::new_attribute("Flash");
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .add_asset::<AnimatorPreset>()
+        .add_systems(Update, apply_animator_presets);
+    let handle = app.world.resource_mut::<Assets<AnimatorPreset>>().add(
+        AnimatorPreset::new(NodeId::U64(0))
+            .with_temporary(fall.clone())
+            .with_lifetime(flash.clone(), Lifetime::Seconds(0.3)),
+    );
+    let entity = app.world.spawn(AnimatorBundle::new(handle.clone())).id();
+    app.update();
+    app.update();
+    let state = app.world.get::<AnimationState>(entity).unwrap();
+    assert!(state.is_temporary(&fall));
+    assert!(state.is_temporary(&flash));
+
+    *app.world
+        .resource_mut::<Assets<AnimatorPreset>>()
+        .get_mut(&handle)
+        .unwrap() = AnimatorPreset::new(NodeId::U64(1)).with_temporary(fall.clone());
+    app.update();
+    app.update();
+    let state = app.world.get::<AnimationState>(entity).unwrap();
+    assert!(state.is_temporary(&fall));
+    assert!(!state.is_temporary(&flash));
+    assert_eq!(
+        app.world.get::<StartNode>(entity).unwrap().0,
+        NodeId::U64(1)
+    );
+}
@@ -1,11 +1,13 @@
 use std::borrow::Cow;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::marker::PhantomData;
 
 use bevy::reflect::Reflect;
 use bevy::reflect::ReflectDeserialize;
 use bevy::reflect::ReflectSerialize;
 
+use crate::names::{lookup_name, record_name};
 use crate::utils::get_node_hash;
 
 #[derive(Default, Reflect, PartialOrd, Ord, strum_macros::AsRefStr)]
@@ -21,6 +23,14 @@ pub enum Attribute {
     FlipX,
     FlipY,
     LastFPS,
+    /// `Color` the sprite is tinted with
+    Color,
+    /// `f32` that replaces the alpha of the sprite color
+    Alpha,
+    /// `Anchor` of the sprite
+    Anchor,
+    /// `Vec2` custom size of the sprite
+    CustomSize,
     Index(u64, Cow<'static, str>),
     IndexId(u64),
 }
@@ -71,6 +81,10 @@ impl Clone for Attribute {
             Self::FlipX => Self::FlipX,
             Self::FlipY => Self::FlipY,
             Self::LastFPS => Self::LastFPS,
+            Self::Color => Self::Color,
+            Self::Alpha => Self::Alpha,
+            Self::Anchor => Self::Anchor,
+            Self::CustomSize => Self::CustomSize,
             Self::Index(id, _) => Self::IndexId(*id),
             Self::IndexId(arg0) => Self::IndexId(arg0.clone()),
         }
@@ -114,6 +128,10 @@ impl Attribute {
             Self::FlipX => Self::FlipX,
             Self::FlipY => Self::FlipY,
             Self::LastFPS => Self::LastFPS,
+            Self::Color => Self::Color,
+            Self::Alpha => Self::Alpha,
+            Self::Anchor => Self::Anchor,
+            Self::CustomSize => Self::CustomSize,
             Self::Index(id, arg0) => Self::Index(*id, arg0.clone()),
             Self::IndexId(arg0) => Self::IndexId(arg0.clone()),
         }
@@ -124,25 +142,33 @@ impl Attribute {
     pub fn from_str(s: &str) -> Attribute {
         match ron::from_str::<Attribute>(s) {
             Ok(ok) => ok,
-            Err(_) => Attribute::Custom(get_node_hash(&s), s.to_string().into()),
+            Err(_) => Attribute::new_attribute(s.to_string()),
         }
     }
 }
 
 impl std::fmt::Display for Attribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.as_ref())?;
         match self {
             Attribute::Custom(_, name) | Attribute::Index(_, name) => {
+                f.write_str(self.as_ref())?;
                 f.write_str("::")?;
                 f.write_str(name.as_ref())
             }
-            Attribute::CustomId(_) | Attribute::IndexId(_) => {
-                f.write_str("(")?;
-                f.write_str(self.name_or_id().as_ref())?;
-                f.write_str(")")
-            }
-            _ => Ok(()),
+            Attribute::CustomId(id) | Attribute::IndexId(id) => match lookup_name(*id) {
+                Some(name) => {
+                    f.write_str(if self.is_index() { "Index" } else { "Custom" })?;
+                    f.write_str("::")?;
+                    f.write_str(&name)
+                }
+                None => {
+                    f.write_str(self.as_ref())?;
+                    f.write_str("(")?;
+                    f.write_str(self.name_or_id().as_ref())?;
+                    f.write_str(")")
+                }
+            },
+            _ => f.write_str(self.as_ref()),
         }
     }
 }
@@ -173,7 +199,9 @@ impl Attribute {
     #[inline(always)]
     pub fn new_index(name: impl Into<Cow<'static, str>>) -> Attribute {
         let name = name.into();
-        Attribute::Index(get_node_hash(&name), name)
+        let hash = get_node_hash(&name);
+        record_name(hash, &name);
+        Attribute::Index(hash, name)
     }
 
     /// Returns the Attribute::Custom() for the given name
@@ -184,7 +212,9 @@ impl Attribute {
     #[inline(always)]
     pub fn new_attribute(name: impl Into<Cow<'static, str>>) -> Attribute {
         let name = name.into();
-        Attribute::Custom(get_node_hash(&name), name)
+        let hash = get_node_hash(&name);
+        record_name(hash, &name);
+        Attribute::Custom(hash, name)
     }
 
     /// Returns the Attribute::CustomId() for the given name
@@ -223,14 +253,17 @@ impl Attribute {
         }
     }
 
-    /// Returns the name of the Attribute or Index or its hash id if the name has been errased
+    /// Returns the name of the Attribute or Index, if the name has been errased it is looked up in the names that have been hashed
+    /// or the hash id is returned if the name was never seen
     /// You should used name if you only want the name since this will allocate as string to hold the id
     pub fn name_or_id(&self) -> Cow<'_, str> {
         if let Some(name) = self.name() {
             name.into()
         } else {
             match self {
-                Attribute::CustomId(id) | Attribute::IndexId(id) => format!("{}", id).into(),
+                Attribute::CustomId(id) | Attribute::IndexId(id) => {
+                    lookup_name(*id).unwrap_or_else(|| format!("{}", id)).into()
+                }
                 _ => unreachable!("all named variants should return Some(&str) from self.name()"),
             }
         }
@@ -247,6 +280,10 @@ impl Attribute {
             Attribute::FlipX => Variant::FlipX,
             Attribute::FlipY => Variant::FlipY,
             Attribute::LastFPS => Variant::LastFPS,
+            Attribute::Color => Variant::Color,
+            Attribute::Alpha => Variant::Alpha,
+            Attribute::Anchor => Variant::Anchor,
+            Attribute::CustomSize => Variant::CustomSize,
             Attribute::Index(_, _) => Variant::Index,
             Attribute::IndexId(_) => Variant::Index,
         }
@@ -301,6 +338,10 @@ enum Variant {
     FlipX,
     FlipY,
     LastFPS,
+    Color,
+    Alpha,
+    Anchor,
+    CustomSize,
     Index,
 }
 
@@ -328,6 +369,10 @@ impl<'de> serde::de::Visitor<'de> for AttributeVisitor {
             Variant::FlipX => Attribute::FlipX,
             Variant::FlipY => Attribute::FlipY,
             Variant::LastFPS => Attribute::LastFPS,
+            Variant::Color => Attribute::Color,
+            Variant::Alpha => Attribute::Alpha,
+            Variant::Anchor => Attribute::Anchor,
+            Variant::CustomSize => Attribute::CustomSize,
             Variant::Index => v.1.newtype_variant_seed(AttributeVisitor::Index)?,
         })
     }
@@ -337,8 +382,8 @@ impl<'de> serde::de::Visitor<'de> for AttributeVisitor {
         E: serde::de::Error,
     {
         Ok(match self {
-            AttributeVisitor::Custom => Attribute::Custom(get_node_hash(&v), v.into()),
-            AttributeVisitor::Index => Attribute::Index(get_node_hash(&v), v.into()),
+            AttributeVisitor::Custom => Attribute::new_attribute(v),
+            AttributeVisitor::Index => Attribute::new_index(v),
         })
     }
 
@@ -370,6 +415,104 @@ impl<'de> serde::de::DeserializeSeed<'de> for AttributeVisitor {
     }
 }
 
+/// An [`Attribute`] that knows the type of the value stored under it
+/// so [`AnimationState::get`](crate::state::AnimationState::get) and [`AnimationState::set`](crate::state::AnimationState::set) do not need a turbofish
+/// and the type can not be got wrong
+/// ```ignore
+/// let state_key = TypedAttribute::<ZState>::new_attribute("ZombieState");
+/// state.set(&state_key, ZState::Idle);
+/// let flipped = state.get(&core_attribute::FLIP_X);
+/// ```
+pub struct TypedAttribute<T> {
+    attribute: Attribute,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedAttribute<T> {
+    /// the caller has to make sure `T` is the type stored under `attribute`
+    pub const fn from_attribute(attribute: Attribute) -> TypedAttribute<T> {
+        TypedAttribute {
+            attribute,
+            _type: PhantomData,
+        }
+    }
+
+    /// see [`Attribute::new_attribute`]
+    pub fn new_attribute(name: impl Into<Cow<'static, str>>) -> TypedAttribute<T> {
+        TypedAttribute::from_attribute(Attribute::new_attribute(name))
+    }
+
+    /// see [`Attribute::new_attribute_id`]
+    pub fn new_attribute_id<H: Hash>(id: &H) -> TypedAttribute<T> {
+        TypedAttribute::from_attribute(Attribute::new_attribute_id(id))
+    }
+
+    pub fn attribute(&self) -> &Attribute {
+        &self.attribute
+    }
+
+    pub fn into_attribute(self) -> Attribute {
+        self.attribute
+    }
+}
+
+impl TypedAttribute<usize> {
+    /// see [`Attribute::new_index`]
+    pub fn new_index(name: impl Into<Cow<'static, str>>) -> TypedAttribute<usize> {
+        TypedAttribute::from_attribute(Attribute::new_index(name))
+    }
+
+    /// see [`Attribute::new_index_id`]
+    pub fn new_index_id<H: Hash>(id: &H) -> TypedAttribute<usize> {
+        TypedAttribute::from_attribute(Attribute::new_index_id(id))
+    }
+}
+
+impl<T> Clone for TypedAttribute<T> {
+    fn clone(&self) -> Self {
+        TypedAttribute::from_attribute(self.attribute.clone())
+    }
+}
+
+impl<T> std::ops::Deref for TypedAttribute<T> {
+    type Target = Attribute;
+    fn deref(&self) -> &Attribute {
+        &self.attribute
+    }
+}
+
+impl<T> From<TypedAttribute<T>> for Attribute {
+    fn from(value: TypedAttribute<T>) -> Self {
+        value.attribute
+    }
+}
+
+impl<T> std::fmt::Debug for TypedAttribute<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.attribute, std::any::type_name::<T>())
+    }
+}
+
+/// The core attributes with the type the plugin uses for them
+pub mod core_attribute {
+    use super::{Attribute, TypedAttribute};
+    use bevy::prelude::{Color, Vec2};
+    use bevy::sprite::Anchor;
+
+    pub const DELTA: TypedAttribute<f32> = TypedAttribute::from_attribute(Attribute::Delta);
+    pub const FRAMES: TypedAttribute<usize> = TypedAttribute::from_attribute(Attribute::Frames);
+    pub const TIME_THIS_FRAME: TypedAttribute<f32> =
+        TypedAttribute::from_attribute(Attribute::TimeThisFrame);
+    pub const FLIP_X: TypedAttribute<bool> = TypedAttribute::from_attribute(Attribute::FlipX);
+    pub const FLIP_Y: TypedAttribute<bool> = TypedAttribute::from_attribute(Attribute::FlipY);
+    pub const LAST_FPS: TypedAttribute<f32> = TypedAttribute::from_attribute(Attribute::LastFPS);
+    pub const COLOR: TypedAttribute<Color> = TypedAttribute::from_attribute(Attribute::Color);
+    pub const ALPHA: TypedAttribute<f32> = TypedAttribute::from_attribute(Attribute::Alpha);
+    pub const ANCHOR: TypedAttribute<Anchor> = TypedAttribute::from_attribute(Attribute::Anchor);
+    pub const CUSTOM_SIZE: TypedAttribute<Vec2> =
+        TypedAttribute::from_attribute(Attribute::CustomSize);
+}
+
 #[test]
 fn test_serde() {
     let ser_cid = ron::to_string(&Attribute::CustomId(2)).expect("ron to work");
@@ -568,3 +711,21 @@ fn test_hash() {
     let hash_0_1 = hash.finish();
     assert_ne!(hash_0, hash_0_1);
 }
+
+#[test]
+fn typed_attribute() {
+    use crate::state::AnimationState;
+    let mut state = AnimationState::default();
+    state.set(&core_attribute::FLIP_X, true);
+    assert!(*state.get(&core_attribute::FLIP_X).unwrap());
+    assert!(state.changed(&core_attribute::FLIP_X));
+    let key = TypedAttribute::<i32>::new_attribute("Health");
+    state.set(&key, 10);
+    assert_eq!(state.get(&key).ok(), Some(&10));
+    assert_eq!(*state.attribute::<i32>(&key), 10);
+    state.set_attribute(Attribute::new_attribute("Health"), 1.0f32);
+    assert!(matches!(
+        state.get(&key),
+        Err(crate::error::StateError::WrongType)
+    ));
+}
@@ -0,0 +1,266 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy::reflect::{GetPath, GetTypeRegistration};
+
+use crate::prelude::*;
+use crate::state::ReflectAnimationStateObj;
+
+/// Which way an [`AttributeBinding`] copies the value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum BindingDirection {
+    /// copy the component into the state before [`AnimationSet::Update`] and back after it
+    #[default]
+    Both,
+    /// only copy the component into the state
+    ToState,
+    /// only copy changes the nodes make to the attribute back to the component
+    FromState,
+}
+
+impl BindingDirection {
+    fn copies_to_state(&self) -> bool {
+        matches!(self, BindingDirection::Both | BindingDirection::ToState)
+    }
+
+    fn copies_from_state(&self) -> bool {
+        matches!(self, BindingDirection::Both | BindingDirection::FromState)
+    }
+}
+
+/// Keeps the component `C` or a field of it in sync with an attribute on every entity with an [`AnimationState`]
+/// the value is copied into the state when `C` changes and copied back when the nodes change the attribute
+/// values are compared with `Reflect::reflect_partial_eq` so the type should `#[reflect(PartialEq)]`
+/// or it will be written back every time the attribute changes
+/// ```ignore
+/// app.add_plugins(AttributeBinding::<ZState>::new("ZombieState"))
+///     .add_plugins(AttributeBinding::<Transform>::new("Height").with_path("translation.y").with_direction(BindingDirection::ToState));
+/// ```
+pub struct AttributeBinding<C: Component + FromReflect + GetTypeRegistration> {
+    attribute: Attribute,
+    path: Option<Cow<'static, str>>,
+    direction: BindingDirection,
+    _component: PhantomData<C>,
+}
+
+impl<C: Component + FromReflect + GetTypeRegistration> AttributeBinding<C> {
+    /// binds `C` to `Attribute::new_attribute(name)`
+    pub fn new(name: impl Into<Cow<'static, str>>) -> AttributeBinding<C> {
+        AttributeBinding::from_attribute(Attribute::new_attribute(name))
+    }
+
+    pub fn from_attribute(attribute: Attribute) -> AttributeBinding<C> {
+        AttributeBinding {
+            attribute,
+            path: None,
+            direction: BindingDirection::Both,
+            _component: PhantomData,
+        }
+    }
+
+    /// bind the field at `path` rather then the whole component, `path` is a reflect path like `"translation.y"`
+    /// the type of the field must be registered with `#[reflect(AnimationStateObj)]`
+    pub fn with_path(mut self, path: impl Into<Cow<'static, str>>) -> AttributeBinding<C> {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_direction(mut self, direction: BindingDirection) -> AttributeBinding<C> {
+        self.direction = direction;
+        self
+    }
+}
+
+impl<C: Component + FromReflect + GetTypeRegistration> Plugin for AttributeBinding<C> {
+    fn build(&self, app: &mut App) {
+        let binding = Binding {
+            attribute: self.attribute.deep_clone(),
+            path: self.path.clone(),
+            direction: self.direction,
+        };
+        if let Some(mut bindings) = app.world.get_resource_mut::<Bindings<C>>() {
+            bindings.bindings.push(binding);
+            return;
+        }
+        crate::state::register_state_type::<C>(
+            &mut app.world.resource::<AppTypeRegistry>().write(),
+        );
+        app.insert_resource(Bindings::<C> {
+            bindings: vec![binding],
+            _component: PhantomData,
+        });
+        app.add_systems(
+            Update,
+            (
+                bind_to_state::<C>.in_set(AnimationSet::PreUpdate),
+                bind_from_state::<C>.in_set(AnimationSet::PostUpdate),
+            ),
+        );
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+struct Binding {
+    attribute: Attribute,
+    path: Option<Cow<'static, str>>,
+    direction: BindingDirection,
+}
+
+impl Binding {
+    fn get<'a>(&self, component: &'a dyn Reflect) -> Option<&'a dyn Reflect> {
+        match &self.path {
+            Some(path) => component.reflect_path(path).ok(),
+            None => Some(component),
+        }
+    }
+
+    fn get_mut<'a>(&self, component: &'a mut dyn Reflect) -> Option<&'a mut dyn Reflect> {
+        match &self.path {
+            Some(path) => component.reflect_path_mut(path).ok(),
+            None => Some(component),
+        }
+    }
+}
+
+/// The bindings added for `C`
+#[derive(Resource)]
+struct Bindings<C: Component> {
+    bindings: Vec<Binding>,
+    _component: PhantomData<C>,
+}
+
+#[allow(clippy::type_complexity)]
+fn bind_to_state<C: Component + Reflect>(
+    bindings: Res<Bindings<C>>,
+    registry: Res<AppTypeRegistry>,
+    mut query: Query<(&C, &mut AnimationState), Or<(Changed<C>, Added<AnimationState>)>>,
+    mut warned: Local<bool>,
+) {
+    let registry = registry.read();
+    for (component, mut state) in query.iter_mut() {
+        for binding in bindings.bindings.iter() {
+            if !binding.direction.copies_to_state() {
+                continue;
+            }
+            let Some(value) = binding.get(component.as_reflect()) else {continue;};
+            if let Some(true) = state
+                .get_reflect(&binding.attribute)
+                .and_then(|current| current.reflect_partial_eq(value))
+            {
+                continue;
+            }
+            let Some(value) = registry
+                .get_type_data::<ReflectAnimationStateObj>(value.as_any().type_id())
+                .and_then(|from_reflect| from_reflect.from_reflect(value))
+            else {
+                if !*warned {
+                    warn!(
+                        "{} can not be bound to {} it needs #[reflect(AnimationStateObj)]",
+                        value.type_name(),
+                        binding.attribute
+                    );
+                    *warned = true;
+                }
+                continue;
+            };
+            state.set_boxed(binding.attribute.clone(), value);
+        }
+    }
+}
+
+fn bind_from_state<C: Component + Reflect>(
+    bindings: Res<Bindings<C>>,
+    mut query: Query<(&mut C, &AnimationState)>,
+) {
+    for (mut component, state) in query.iter_mut() {
+        let mut changed = false;
+        for binding in bindings.bindings.iter() {
+            if !binding.direction.copies_from_state() || !state.changed(&binding.attribute) {
+                continue;
+            }
+            let Some(value) = state.get_reflect(&binding.attribute) else {continue;};
+            let Some(target) = binding.get_mut(component.bypass_change_detection().as_reflect_mut()) else {continue;};
+            if target.as_any().type_id() != value.as_any().type_id()
+                || target.reflect_partial_eq(value) == Some(true)
+            {
+                continue;
+            }
+            target.apply(value);
+            changed = true;
+        }
+        if changed {
+            component.set_changed();
+        }
+    }
+}
+
+#[test]
+fn binding_both_ways() {
+    #[derive(Debug, Clone, PartialEq, Component, Reflect)]
+    #[reflect(PartialEq)]
+    struct Health(u32);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AttributeBinding::<Health>::new("Health"))
+        .add_plugins(
+            AttributeBinding::<Transform>::new("Height")
+                .with_path("translation.y")
+                .with_direction(BindingDirection::ToState),
+        );
+    crate::state::register_state_type::<f32>(&mut app.world.resource::<AppTypeRegistry>().write());
+    let entity = app
+        .world
+        .spawn((
+            Health(3),
+            Transform::from_xyz(0.0, 2.0, 0.0),
+            AnimationState::default(),
+        ))
+        .id();
+    app.update();
+    let state = app.world.get::<AnimationState>(entity).unwrap();
+    assert_eq!(
+        state.attribute::<Health>(&Attribute::new_attribute("Health")),
+        &Health(3)
+    );
+    assert_eq!(
+        *state.attribute::<f32>(&Attribute::new_attribute("Height")),
+        2.0
+    );
+    let mut state = app.world.get_mut::<AnimationState>(entity).unwrap();
+    state.set_attribute(Attribute::new_attribute("Health"), Health(1));
+    state.set_attribute(Attribute::new_attribute("Height"), 5.0f32);
+    app.update();
+    assert_eq!(app.world.get::<Health>(entity), Some(&Health(1)));
+    assert_eq!(
+        app.world.get::<Transform>(entity).unwrap().translation.y,
+        2.0
+    );
+}
+
+#[test]
+fn binding_state_added_later() {
+    #[derive(Debug, Clone, PartialEq, Component, Reflect)]
+    #[reflect(PartialEq)]
+    struct Stamina(u32);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AttributeBinding::<Stamina>::new("Stamina"));
+    let entity = app.world.spawn(Stamina(7)).id();
+    app.update();
+    // like `AnimatorBundle` the state is added once the preset has loaded
+    app.world
+        .entity_mut(entity)
+        .insert(AnimationState::default());
+    app.update();
+    let state = app.world.get::<AnimationState>(entity).unwrap();
+    assert_eq!(
+        state.attribute::<Stamina>(&Attribute::new_attribute("Stamina")),
+        &Stamina(7)
+    );
+}
@@ -93,6 +93,16 @@ pub enum RunError {
     StateError(#[from] StateError),
     #[error("{0}")]
     Custom(String),
+    #[error(
+        "Call stack is full ({0}), increase AnimationSettings::max_call_depth if this is intended"
+    )]
+    CallStackOverflow(usize),
+    #[error("Return was used but there is nothing to return to")]
+    EmptyCallStack,
+    #[error("Node not found: {0}")]
+    NodeNotFound(crate::prelude::NodeId<'static>),
+    #[error("Ran more then {0} nodes without picking a frame")]
+    OutOfDepth(usize),
 }
 
 #[cfg(feature = "serialize")]
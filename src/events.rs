@@ -0,0 +1,24 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Sent when the animation of `entity` reaches a frame that has a marker on it
+/// if more then one frame is passed in a single update an event is sent for every marked frame passed
+#[derive(Debug, Clone, Event)]
+pub struct AnimationEvent {
+    pub entity: Entity,
+    /// the node the marker is on
+    pub node: NodeId<'static>,
+    pub marker: Cow<'static, str>,
+    /// the index of the marked frame in the node
+    pub frame: usize,
+}
+
+/// Sent when the image or atlas index of `entity` was changed by the animation this update
+/// this is not sent when the nodes pick the same frame that is already showing
+#[derive(Debug, Clone, Event)]
+pub struct FrameChanged {
+    pub entity: Entity,
+}
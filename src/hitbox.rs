@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A shape in sprite-local space
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, serde::Deserialize, serde::Serialize)]
+pub enum HitShape {
+    Rect { center: Vec2, size: Vec2 },
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl HitShape {
+    pub fn center(&self) -> Vec2 {
+        match self {
+            HitShape::Rect { center, .. } | HitShape::Circle { center, .. } => *center,
+        }
+    }
+
+    /// mirrors the shape the same way the sprite is mirrored
+    pub fn flipped(mut self, flip_x: bool, flip_y: bool) -> HitShape {
+        let (HitShape::Rect { center, .. } | HitShape::Circle { center, .. }) = &mut self;
+        if flip_x {
+            center.x = -center.x;
+        }
+        if flip_y {
+            center.y = -center.y;
+        }
+        self
+    }
+}
+
+/// A named shape that is active while its frame is showing
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Hitbox {
+    pub name: Cow<'static, str>,
+    pub shape: HitShape,
+}
+
+/// The hitboxes of the frame an entity is showing, mirrored by [`Attribute::FlipX`] and [`Attribute::FlipY`]
+/// add this to any entity with an [`AnimationState`] that needs its hitboxes
+#[derive(Debug, Default, Clone, Component, Reflect)]
+#[reflect(Component)]
+pub struct ActiveHitboxes(pub Vec<Hitbox>);
+
+impl ActiveHitboxes {
+    pub fn get<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a HitShape> {
+        self.0
+            .iter()
+            .filter(move |hitbox| hitbox.name == name)
+            .map(|hitbox| &hitbox.shape)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Hitbox> {
+        self.0.iter()
+    }
+}
+
+pub(crate) fn update_hitboxes(mut query: Query<(&AnimationState, &mut ActiveHitboxes)>) {
+    for (state, mut active) in query.iter_mut() {
+        let flip_x = state
+            .get_attribute::<bool>(&Attribute::FlipX)
+            .cloned()
+            .unwrap_or_default();
+        let flip_y = state
+            .get_attribute::<bool>(&Attribute::FlipY)
+            .cloned()
+            .unwrap_or_default();
+        let hitboxes = state
+            .hitboxes()
+            .iter()
+            .map(|hitbox| (&hitbox.name, hitbox.shape.flipped(flip_x, flip_y)));
+        // only write when the frame changes the hitboxes so Changed<ActiveHitboxes> stays useful
+        if !active
+            .0
+            .iter()
+            .map(|hitbox| (&hitbox.name, hitbox.shape))
+            .eq(hitboxes.clone())
+        {
+            active.0 = hitboxes
+                .map(|(name, shape)| Hitbox {
+                    name: name.clone(),
+                    shape,
+                })
+                .collect();
+        }
+    }
+}
+
+#[test]
+fn hitbox_ron() {
+    let hitboxes: Vec<(usize, String, HitShape)> =
+        ron::from_str("[(5, \"Hit\", Rect(center: (1., 2.), size: (3., 4.)))]").unwrap();
+    assert_eq!(
+        hitboxes[0].2,
+        HitShape::Rect {
+            center: Vec2::new(1., 2.),
+            size: Vec2::new(3., 4.)
+        }
+    );
+}
+
+#[test]
+fn flip_hitbox() {
+    let shape = HitShape::Circle {
+        center: Vec2::new(2.0, 3.0),
+        radius: 1.0,
+    };
+    assert_eq!(shape.flipped(true, false).center(), Vec2::new(-2.0, 3.0));
+    assert_eq!(shape.flipped(true, true).center(), Vec2::new(-2.0, -3.0));
+}
@@ -1,7 +1,9 @@
 use crate::error::BevySpriteAnimationError as Error;
 use crate::prelude::*;
+use bevy::asset::HandleId;
 use bevy::prelude::*;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 pub(crate) mod utils {
     use std::hash::Hasher;
@@ -19,16 +21,28 @@ pub(crate) mod utils {
 }
 
 mod error;
+mod slots;
 
 pub mod serde;
 
 pub mod prelude;
 
+pub mod animator;
 pub mod attributes;
+pub mod binding;
+pub mod events;
+pub mod hitbox;
+pub mod names;
 pub mod node_core;
 pub mod nodes;
+pub mod playback;
+pub mod program;
+pub mod root_motion;
+pub mod simulate;
 pub mod state;
 pub mod system_set;
+pub mod target;
+pub mod timers;
 
 pub mod node_id;
 
@@ -45,21 +59,68 @@ mod test {
 }
 
 /// The plugin that adds all you need for the Animation sytem
-/// The const is the max number of nodes that are to be run per entity per frame
-/// This is to stop infinity looping, you should be abel to see this high if you have no nodes that loop
-/// This will only report as a warning when the max depth is reached so please dont set it too high if there is a potental to loop
-/// start small get bigger, keep it as small as you can whithout rist of breaking early
-pub struct SpriteAnimationPlugin<const MAXDEPTH: usize>;
+/// `max_depth` is the starting value of [`AnimationSettings::max_depth`]
+/// if `AnimationSettings` is already in the app it will be left as is
+pub struct SpriteAnimationPlugin {
+    pub max_depth: usize,
+}
+
+impl Default for SpriteAnimationPlugin {
+    fn default() -> Self {
+        SpriteAnimationPlugin { max_depth: 10 }
+    }
+}
 
-impl<const MAX: usize> Plugin for SpriteAnimationPlugin<MAX> {
+impl Plugin for SpriteAnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_asset::<AnimationNode>();
+        app.add_asset::<AnimationNode>()
+            .add_asset::<animator::AnimatorPreset>();
+        if !app.world.contains_resource::<AnimationSettings>() {
+            app.insert_resource(AnimationSettings {
+                max_depth: self.max_depth,
+                ..Default::default()
+            });
+        }
         #[cfg(feature = "serialize")]
         app.add_plugins(crate::serde::AnimationNodeSerdePlugin);
         app.add_systems(First, state::clear_changed);
+        app.init_resource::<program::AnimationPrograms>();
+        app.add_plugins(target::AnimationTargetPlugin::<UiImage>::default());
         app.add_systems(Update, state::update_delta.in_set(AnimationSet::PreUpdate));
-        app.add_systems(Update, animation_system::<MAX>.in_set(AnimationSet::Update));
+        app.add_systems(
+            Update,
+            state::seed_new_states.in_set(AnimationSet::PreUpdate),
+        );
+        app.add_systems(
+            Update,
+            timers::advance_timers
+                .after(state::update_delta)
+                .in_set(AnimationSet::PreUpdate),
+        );
+        app.add_systems(
+            Update,
+            program::compile_programs.in_set(AnimationSet::PreUpdate),
+        );
+        app.add_systems(Update, animation_system.in_set(AnimationSet::Update));
         app.add_systems(Update, state::flip_update.in_set(AnimationSet::PostUpdate));
+        app.add_systems(
+            Update,
+            state::sprite_update.in_set(AnimationSet::PostUpdate),
+        );
+        app.add_systems(
+            Update,
+            root_motion::apply_root_motion.in_set(AnimationSet::PostUpdate),
+        );
+        app.add_systems(
+            Update,
+            hitbox::update_hitboxes.in_set(AnimationSet::PostUpdate),
+        );
+        app.add_systems(
+            Update,
+            state::send_animation_events.in_set(AnimationSet::PostUpdate),
+        );
+        app.add_event::<AnimationEvent>()
+            .add_event::<FrameChanged>();
         app.add_systems(Last, state::clear_unchanged_temp);
         app.configure_sets(
             Update,
@@ -71,7 +132,27 @@ impl<const MAX: usize> Plugin for SpriteAnimationPlugin<MAX> {
                 .chain(),
         );
         nodes::type_registration::registor_nodes(app);
-        app.register_type::<StartNode>();
+        app.register_type::<StartNode>()
+            .register_type::<AnimationSettings>()
+            .register_type::<playback::AnimationPlayback>()
+            .register_type::<playback::TimeSource>()
+            .register_type::<root_motion::NoRootMotion>()
+            .register_type::<hitbox::ActiveHitboxes>()
+            .register_type::<timers::Lifetime>()
+            .register_type::<timers::AttributeTimer>()
+            .register_type::<AnimationState>();
+        {
+            let registry = app.world.resource::<AppTypeRegistry>();
+            let mut types = registry.write();
+            state::register_core_state_types(&mut types);
+            state::use_app_state_types(registry);
+        }
+        #[cfg(feature = "serialize")]
+        {
+            let registry = app.world.resource::<AppTypeRegistry>().clone();
+            app.add_asset_loader(animator::AnimatorLoader(registry));
+        }
+        app.add_systems(PreUpdate, animator::apply_animator_presets);
         #[cfg(feature = "dot")]
         app.add_systems(Update, dot::write_dot);
         #[cfg(feature = "editor")]
@@ -79,9 +160,34 @@ impl<const MAX: usize> Plugin for SpriteAnimationPlugin<MAX> {
     }
 }
 
+/// Settings used by the animation system; these can be changed at runtime
+#[derive(Debug, Clone, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct AnimationSettings {
+    /// The max number of nodes that are to be run per entity per frame
+    /// This is to stop infinity looping, you should be abel to see this high if you have no nodes that loop
+    /// When it is reached the nodes that were run are reported as a warning,
+    /// once per entity until it runs within the budget again or the nodes change
+    /// start small get bigger, keep it as small as you can whithout rist of breaking early
+    pub max_depth: usize,
+    /// The max number of [`NodeResult::Call`] that can be waiting for a [`NodeResult::Return`] at once
+    pub max_call_depth: usize,
+}
+
+impl Default for AnimationSettings {
+    fn default() -> Self {
+        AnimationSettings {
+            max_depth: 10,
+            max_call_depth: 8,
+        }
+    }
+}
+
 #[derive(bevy::reflect::TypeUuid, bevy::reflect::TypePath)]
 #[uuid = "b30eb8be-06db-4d7c-922d-22767a539ad6"]
-pub struct AnimationNode(pub Box<dyn AnimationNodeTrait>);
+/// The node is shared with the [`AnimationProgram`](program::AnimationProgram)s it is compiled into,
+/// so it can only be changed through `&mut` while no program holds it; replace it with `Assets::set` instead
+pub struct AnimationNode(pub Arc<dyn AnimationNodeTrait>);
 
 impl bevy::reflect::Reflect for AnimationNode {
     fn type_name(&self) -> &str {
@@ -93,7 +199,7 @@ impl bevy::reflect::Reflect for AnimationNode {
     }
 
     fn into_any(self: Box<Self>) -> Box<dyn std::any::Any> {
-        self.0.into_any()
+        self
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -101,11 +207,11 @@ impl bevy::reflect::Reflect for AnimationNode {
     }
 
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self.0.as_any_mut()
+        self.node_mut().as_any_mut()
     }
 
     fn into_reflect(self: Box<Self>) -> Box<dyn Reflect> {
-        self.0.into_reflect()
+        self
     }
 
     fn as_reflect(&self) -> &dyn Reflect {
@@ -113,15 +219,15 @@ impl bevy::reflect::Reflect for AnimationNode {
     }
 
     fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
-        self.0.as_reflect_mut()
+        self.node_mut().as_reflect_mut()
     }
 
     fn apply(&mut self, value: &dyn Reflect) {
-        self.0.apply(value)
+        self.node_mut().apply(value)
     }
 
     fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
-        self.0.set(value)
+        self.node_mut().set(value)
     }
 
     fn reflect_ref(&self) -> bevy::reflect::ReflectRef {
@@ -129,11 +235,11 @@ impl bevy::reflect::Reflect for AnimationNode {
     }
 
     fn reflect_mut(&mut self) -> bevy::reflect::ReflectMut {
-        self.0.reflect_mut()
+        self.node_mut().reflect_mut()
     }
 
     fn reflect_owned(self: Box<Self>) -> bevy::reflect::ReflectOwned {
-        self.0.reflect_owned()
+        bevy::reflect::ReflectOwned::Value(self)
     }
 
     fn clone_value(&self) -> Box<dyn Reflect> {
@@ -143,7 +249,12 @@ impl bevy::reflect::Reflect for AnimationNode {
 
 impl AnimationNode {
     pub fn new(node: impl AnimationNodeTrait) -> AnimationNode {
-        AnimationNode(Box::new(node))
+        AnimationNode(Arc::new(node))
+    }
+
+    fn node_mut(&mut self) -> &mut dyn AnimationNodeTrait {
+        Arc::get_mut(&mut self.0)
+            .expect("an AnimationNode can not be changed while a program holds it, use Assets::set")
     }
 
     pub fn downcast_ref<T: std::any::Any>(&self) -> Option<&T> {
@@ -175,7 +286,10 @@ impl<'a> AnimationNodeTrait for AnimationNode {
         self.0.dot(this, out, asset_server)
     }
     fn set_id(&mut self, id: NodeId<'_>) {
-        self.0.set_id(id)
+        self.node_mut().set_id(id)
+    }
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        self.0.successors()
     }
 }
 
@@ -214,44 +328,187 @@ impl StartNode {
     }
 }
 
-fn animation_system<const MAX: usize>(
+#[allow(clippy::type_complexity)]
+fn animation_system(
     nodes: Res<Assets<AnimationNode>>,
-    mut query: Query<(&mut state::AnimationState, &mut Handle<Image>, &StartNode)>,
-    debug_nodes: Query<&StartNode>,
+    settings: Res<AnimationSettings>,
+    programs: Res<program::AnimationPrograms>,
+    mut query: Query<(
+        Entity,
+        &mut state::AnimationState,
+        Option<&mut Handle<Image>>,
+        Option<(&mut Handle<TextureAtlas>, &mut TextureAtlasSprite)>,
+        &StartNode,
+    )>,
+    mut reported: Local<bevy::utils::HashSet<Entity>>,
+    mut removed: RemovedComponents<state::AnimationState>,
+    mut node_events: EventReader<AssetEvent<AnimationNode>>,
 ) {
+    // a changed graph may run out of budget in a new way so the entities running it are reported again
+    let changed = node_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => Some(handle.id()),
+            AssetEvent::Created { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    if !changed.is_empty() {
+        reported.retain(|entity| {
+            let Ok((.., start)) = query.get(*entity) else {return false;};
+            programs
+                .get(&start.0)
+                .is_some_and(|program| !changed.iter().any(|id| program.contains(id)))
+        });
+    }
+    for entity in removed.iter() {
+        reported.remove(&entity);
+    }
+    let out_of_depth = std::sync::Mutex::new(Vec::new());
+    let within_budget = std::sync::Mutex::new(Vec::new());
     query
         .par_iter_mut()
-        .for_each_mut(|(mut state, mut image, start)| {
-            let mut next = NodeResult::Next(start.0.clone());
-            trace!("Starting With: {:?}", start.0);
-            'main: for _ in 0..MAX {
-                match next {
-                    NodeResult::Next(id) => {
-                        if let Some(node) = nodes.get(&Handle::weak(id.to_static().into())) {
-                            trace!("Running Node: {:?}", id);
-                            next = match node.run(&mut state) {
-                                Ok(ok) => ok,
-                                Err(e) => {
-                                    error!("{}", e);
-                                    break;
-                                }
-                            }
-                        } else {
-                            for node in debug_nodes.iter() {
-                                if node.0 == id {
-                                    error!("Node not found: {}", node.0);
-                                    break 'main;
-                                }
-                            }
-                            error!("Node not found: {:?}", id);
-                            break;
+        .for_each_mut(|(entity, mut state, image, atlas, start)| {
+            let program = programs.get(&start.0);
+            match run_nodes(&start.0, &mut state, &nodes, program, &settings) {
+                Ok(()) => {
+                    if reported.contains(&entity) {
+                        if let Ok(mut within_budget) = within_budget.lock() {
+                            within_budget.push(entity);
                         }
                     }
-                    NodeResult::Done(h) => {
-                        *image = h;
-                        break;
+                }
+                Err(RunError::OutOfDepth(_)) => {
+                    if let Ok(mut out_of_depth) = out_of_depth.lock() {
+                        out_of_depth.push(entity);
                     }
                 }
+                Err(e) => error!("{}", e),
             }
-        })
+            // only write when the frame flips so Changed<Handle<Image>> stays useful
+            let frame_changed = match (&state.output, image, atlas) {
+                (Some(target::AnimationOutput::Image(h)), Some(mut image), _) if *image != *h => {
+                    *image = h.clone();
+                    true
+                }
+                (
+                    Some(target::AnimationOutput::Atlas(h, index)),
+                    _,
+                    Some((mut atlas, mut sprite)),
+                ) => {
+                    let mut changed = false;
+                    if *atlas != *h {
+                        *atlas = h.clone();
+                        changed = true;
+                    }
+                    if sprite.index != *index {
+                        sprite.index = *index;
+                        changed = true;
+                    }
+                    changed
+                }
+                // the output is kept in the state for any other AnimationTarget on this entity
+                _ => false,
+            };
+            if frame_changed {
+                state.frame_changed = true;
+            }
+        });
+    for entity in within_budget.into_inner().unwrap_or_default() {
+        reported.remove(&entity);
+    }
+    for entity in out_of_depth.into_inner().unwrap_or_default() {
+        if !reported.insert(entity) {
+            continue;
+        }
+        let Ok((_, state, ..)) = query.get(entity) else {continue;};
+        warn!(
+            "{:?} ran out of node budget ({}); {}",
+            entity,
+            settings.max_depth,
+            describe_path(&state.visited, &nodes)
+        );
+    }
+}
+
+/// Runs the nodes from `start` until one of them picks a frame, the frame is stored in [`AnimationState::output`]
+/// `program` is used to find nodes before looking in `nodes`
+pub(crate) fn run_nodes(
+    start: &NodeId<'_>,
+    state: &mut state::AnimationState,
+    nodes: &Assets<AnimationNode>,
+    program: Option<&program::AnimationProgram>,
+    settings: &AnimationSettings,
+) -> Result<(), RunError> {
+    // the index in the program of the node that is being run
+    let mut at = None;
+    let mut next = NodeResult::Next(start.to_static());
+    state.visited.clear();
+    state.call_stack.clear();
+    state.output = None;
+    state.hitboxes.clear();
+    trace!("Starting With: {:?}", start);
+    loop {
+        match next {
+            NodeResult::Next(id) => {
+                if state.visited.len() >= settings.max_depth {
+                    return Err(RunError::OutOfDepth(settings.max_depth));
+                }
+                let handle = HandleId::from(&id);
+                state.visited.push(handle);
+                let found = program.and_then(|program| program.find_node(at, handle));
+                at = found.map(|(index, _)| index);
+                let Some(node) = found.map(|(_, node)| node).or_else(|| nodes.get(&Handle::weak(handle)).map(|node| node.0.as_ref())) else {return Err(RunError::NodeNotFound(id));};
+                trace!("Running Node: {:?}", id);
+                next = node.run(state)?;
+            }
+            NodeResult::Call(id, then) => {
+                if state.call_stack.len() >= settings.max_call_depth {
+                    return Err(RunError::CallStackOverflow(settings.max_call_depth));
+                }
+                trace!("Calling: {:?} then {:?}", id, then);
+                state.call_stack.push(then);
+                next = NodeResult::Next(id);
+            }
+            NodeResult::Return => {
+                let Some(then) = state.call_stack.pop() else {return Err(RunError::EmptyCallStack);};
+                next = NodeResult::Next(then);
+            }
+            NodeResult::Done(h) => {
+                state.output = Some(target::AnimationOutput::Image(h));
+                return Ok(());
+            }
+            NodeResult::DoneAtlas(h, index) => {
+                state.output = Some(target::AnimationOutput::Atlas(h, index));
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Describes the nodes that were run when an entity ran out of node budget,
+/// if a node was run more then once only the loop is shown
+fn describe_path(visited: &[HandleId], nodes: &Assets<AnimationNode>) -> String {
+    let name = |id: &HandleId| {
+        let node = handle_to_node(*id);
+        match nodes.get(&Handle::weak(*id)) {
+            Some(found) => format!("{} ({})", found.name(), node),
+            None => node.to_string(),
+        }
+    };
+    let Some(last) = visited.last() else {
+        return "no nodes were run".to_string();
+    };
+    if let Some(start) = visited[..visited.len() - 1]
+        .iter()
+        .rposition(|id| id == last)
+    {
+        let cycle = visited[start..].iter().map(name).collect::<Vec<_>>();
+        format!("looping: {}", cycle.join(" -> "))
+    } else {
+        let chain = visited.iter().map(name).collect::<Vec<_>>();
+        format!(
+            "no loop found, increase AnimationSettings::max_depth if this chain is intended: {}",
+            chain.join(" -> ")
+        )
+    }
 }
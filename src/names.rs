@@ -0,0 +1,53 @@
+use std::cell::RefCell;
+use std::sync::{OnceLock, RwLock};
+
+use bevy::utils::{HashMap, HashSet};
+
+/// Every name that has been hashed into an [`Attribute`](crate::attributes::Attribute) or [`NodeId`](crate::node_id::NodeId)
+/// so ids that have lost their name when cloned can still be shown by name
+static NAMES: OnceLock<RwLock<HashMap<u64, Box<str>>>> = OnceLock::new();
+
+thread_local! {
+    /// the hashes this thread has already recorded, so names made every frame do not take the lock
+    static RECORDED: RefCell<HashSet<u64>> = RefCell::default();
+}
+
+fn names() -> &'static RwLock<HashMap<u64, Box<str>>> {
+    NAMES.get_or_init(Default::default)
+}
+
+/// remembers that `hash` was made from `name`
+/// this is done by `Attribute::new_attribute`, `Attribute::new_index` and `NodeId::from_name`
+/// only the first time a thread sees a hash takes the lock
+pub fn record_name(hash: u64, name: &str) {
+    if !RECORDED.with(|recorded| recorded.borrow_mut().insert(hash)) {
+        return;
+    }
+    if names().read().unwrap().contains_key(&hash) {
+        return;
+    }
+    names().write().unwrap().insert(hash, name.into());
+}
+
+/// the name that was hashed into `hash`, `None` if no name has been seen for it
+pub fn lookup_name(hash: u64) -> Option<String> {
+    names()
+        .read()
+        .unwrap()
+        .get(&hash)
+        .map(|name| name.to_string())
+}
+
+#[test]
+fn lookup_recorded() {
+    use crate::prelude::*;
+    let attribute = Attribute::new_attribute("RecordedAttribute");
+    assert_eq!(
+        attribute.clone().name_or_id(),
+        Attribute::new_attribute("RecordedAttribute").name_or_id()
+    );
+    assert_eq!(attribute.clone().to_string(), "Custom::RecordedAttribute");
+    let node = NodeId::from_name("RecordedNode");
+    assert_eq!(node.to_static().to_string(), "NodeName(\"RecordedNode\")");
+    assert_eq!(lookup_name(12345), None);
+}
@@ -34,12 +34,24 @@ pub trait AnimationNodeTrait: Reflect {
     }
 
     fn set_id(&mut self, id: NodeId<'_>);
+
+    /// The nodes this node can return in [`NodeResult::Next`]
+    /// these are used to compile the graph into an [`AnimationProgram`](crate::program::AnimationProgram)
+    /// nodes that are not listed here will still run but will be looked up in `Assets<AnimationNode>`
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug)]
 pub enum NodeResult {
     Next(NodeId<'static>),
     Done(Handle<Image>),
+    DoneAtlas(Handle<TextureAtlas>, usize),
+    /// Run the first node, when a node returns [`NodeResult::Return`] the second node is run
+    Call(NodeId<'static>, NodeId<'static>),
+    /// Run the node that was given as the second node of the last [`NodeResult::Call`]
+    Return,
 }
 
 impl std::fmt::Display for NodeResult {
@@ -47,6 +59,11 @@ impl std::fmt::Display for NodeResult {
         match self {
             NodeResult::Next(id) => f.write_fmt(format_args!("Next({:#?})", id)),
             NodeResult::Done(_) => f.write_str("Done"),
+            NodeResult::DoneAtlas(_, index) => f.write_fmt(format_args!("DoneAtlas({})", index)),
+            NodeResult::Call(id, then) => {
+                f.write_fmt(format_args!("Call({:#?}, then {:#?})", id, then))
+            }
+            NodeResult::Return => f.write_str("Return"),
         }
     }
 }
@@ -111,7 +111,7 @@ impl<'de> serde::de::Visitor<'de> for NodeVisitor {
     where
         E: serde::de::Error,
     {
-        Ok(NodeId::Name(get_node_hash(&v), v.into()))
+        Ok(NodeId::from_name(v))
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
@@ -214,7 +214,10 @@ impl std::fmt::Display for NodeId<'_> {
         match self {
             NodeId::Name(_, name) => f.write_fmt(format_args!("NodeName(\"{}\")", name)),
             NodeId::U64(id) => f.write_fmt(format_args!("NodeId({})", id)),
-            NodeId::Hash(id) => f.write_fmt(format_args!("NodeName({})", id)),
+            NodeId::Hash(id) => match crate::names::lookup_name(*id) {
+                Some(name) => f.write_fmt(format_args!("NodeName(\"{}\")", name)),
+                None => f.write_fmt(format_args!("NodeName({})", id)),
+            },
             NodeId::Handle(_) => f.write_str("NodeHandle()"),
         }
     }
@@ -242,6 +245,17 @@ impl From<NodeId<'_>> for bevy::asset::HandleId {
     }
 }
 
+impl From<&NodeId<'_>> for bevy::asset::HandleId {
+    fn from(value: &NodeId) -> Self {
+        match value {
+            NodeId::Name(id, _) => bevy::asset::HandleId::Id(NodeId::FROM_NAME, *id),
+            NodeId::U64(id) => bevy::asset::HandleId::Id(NodeId::FROM_ID, *id),
+            NodeId::Hash(id) => bevy::asset::HandleId::Id(NodeId::FROM_NAME, *id),
+            NodeId::Handle(handle) => handle.id(),
+        }
+    }
+}
+
 use std::borrow::Cow;
 
 use bevy::{asset::HandleId, prelude::Handle, reflect::Reflect};
@@ -269,7 +283,16 @@ impl NodeId<'static> {
 impl<'a> NodeId<'a> {
     pub fn from_name(name: impl Into<Cow<'a, str>>) -> NodeId<'a> {
         let name = name.into();
-        NodeId::Name(get_node_hash(&name), name)
+        let hash = get_node_hash(&name);
+        crate::names::record_name(hash, &name);
+        NodeId::Name(hash, name)
+    }
+}
+
+impl NodeId<'_> {
+    /// an index attribute only the node with this id uses, for nodes that keep something in the state between runs
+    pub(crate) fn private_index(&self) -> crate::Attribute {
+        crate::Attribute::new_index_id(&bevy::asset::HandleId::from(self))
     }
 }
 
@@ -0,0 +1,85 @@
+use crate::prelude::*;
+use crate::serde::ReflectLoadNode;
+use bevy::reflect::Reflect;
+use bevy::reflect::ReflectDeserialize;
+use bevy::reflect::ReflectSerialize;
+
+/// Runs `call` as a subgraph, when the subgraph reaches a [`ReturnNode`] `then` is run
+#[derive(serde::Serialize, serde::Deserialize, Reflect)]
+#[reflect(Serialize, Deserialize, LoadNode)]
+pub struct CallNode {
+    #[serde(default)]
+    id: Option<NodeId<'static>>,
+    name: String,
+    call: NodeId<'static>,
+    then: NodeId<'static>,
+}
+
+impl crate::serde::LoadNode for CallNode {
+    fn load<'b>(
+        s: &str,
+        _load_context: &mut bevy::asset::LoadContext<'b>,
+        _dependencies: &mut Vec<bevy::asset::AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let node = ron::from_str::<CallNode>(s)?;
+        Ok(AnimationNode::new(node))
+    }
+}
+
+impl CallNode {
+    pub fn new(
+        name: &str,
+        call: impl Into<NodeId<'static>>,
+        then: impl Into<NodeId<'static>>,
+    ) -> CallNode {
+        CallNode {
+            id: None,
+            name: name.to_string(),
+            call: call.into(),
+            then: then.into(),
+        }
+    }
+}
+
+impl AnimationNodeTrait for CallNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, _: &mut AnimationState) -> Result<NodeResult, RunError> {
+        Ok(NodeResult::Call(
+            self.call.to_static(),
+            self.then.to_static(),
+        ))
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+    }
+
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        vec![self.call.to_static(), self.then.to_static()]
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        out.push_str(&format!(" [label=\"{}\"];\n", self.name));
+        this.dot(out);
+        out.push_str(" -> ");
+        self.call.dot(out);
+        out.push_str(" [label=\"call\", style=bold, color=blue];\n");
+        this.dot(out);
+        out.push_str(" -> ");
+        self.then.dot(out);
+        out.push_str(" [label=\"then\", style=dashed];\n");
+    }
+}
@@ -0,0 +1,284 @@
+use crate::prelude::*;
+use crate::serde::ReflectLoadNode;
+use bevy::prelude::Vec2;
+use bevy::reflect::Reflect;
+use bevy::reflect::ReflectDeserialize;
+use bevy::reflect::ReflectSerialize;
+
+/// A range of directions and the node to go to when facing inside it
+/// angles are in degrees counter-clockwise from the positive x axis, `from` can be negative to cross `0`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, Reflect)]
+pub struct DirectionSector {
+    pub from: f32,
+    pub to: f32,
+    pub next: NodeId<'static>,
+    /// sets [`Attribute::FlipX`] so `next` can be the mirrored clip of another direction
+    #[serde(default)]
+    pub flip_x: bool,
+}
+
+impl DirectionSector {
+    fn contains(&self, degrees: f32) -> bool {
+        let width = (self.to - self.from).rem_euclid(360.);
+        let width = if width == 0. { 360. } else { width };
+        (degrees - self.from).rem_euclid(360.) < width
+    }
+}
+
+/// Goes to the node of the sector `facing` points into
+/// `facing` can be a `Vec2` or an `f32` angle in radians, the sectors are in degrees
+/// if any sector is mirrored [`Attribute::FlipX`] is set for every sector so turning back un-flips the sprite
+/// if `hold` is set the index of the picked sector is kept in it and used while `facing` is `Vec2::ZERO`
+/// ```ron
+/// DirectionNode(
+///     name: "Walk",
+///     facing: Custom("Facing"),
+///     sectors: [
+///         (from: -45, to: 45, next: Id(1)),
+///         (from: 45, to: 135, next: Id(2)),
+///         (from: 135, to: 225, next: Id(1), flip_x: true),
+///         (from: 225, to: 315, next: Id(3)),
+///     ],
+///     hold: Some(Index("Facing")),
+/// )
+/// ```
+#[derive(serde::Serialize, serde::Deserialize, Reflect)]
+#[reflect(Serialize, Deserialize, LoadNode)]
+pub struct DirectionNode {
+    #[serde(default)]
+    id: Option<NodeId<'static>>,
+    name: String,
+    facing: Attribute,
+    #[serde(default)]
+    sectors: Vec<DirectionSector>,
+    #[serde(default)]
+    hold: Option<Attribute>,
+}
+
+impl crate::serde::LoadNode for DirectionNode {
+    fn load<'b>(
+        s: &str,
+        _load_context: &mut bevy::asset::LoadContext<'b>,
+        _dependencies: &mut Vec<bevy::asset::AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let node = ron::from_str::<DirectionNode>(s)?;
+        Ok(AnimationNode::new(node))
+    }
+}
+
+impl DirectionNode {
+    /// a node with no sectors, add them with [`DirectionNode::with_sector`]
+    pub fn new(name: &str, facing: Attribute) -> DirectionNode {
+        DirectionNode {
+            id: None,
+            name: name.to_string(),
+            facing,
+            sectors: Vec::new(),
+            hold: None,
+        }
+    }
+
+    /// equal sectors centered on right, up, left and down
+    pub fn four(name: &str, facing: Attribute, directions: [NodeId<'static>; 4]) -> DirectionNode {
+        DirectionNode::even(name, facing, directions)
+    }
+
+    /// equal sectors centered on right, up-right, up, up-left, left, down-left, down and down-right
+    pub fn eight(name: &str, facing: Attribute, directions: [NodeId<'static>; 8]) -> DirectionNode {
+        DirectionNode::even(name, facing, directions)
+    }
+
+    fn even<const N: usize>(
+        name: &str,
+        facing: Attribute,
+        directions: [NodeId<'static>; N],
+    ) -> DirectionNode {
+        let width = 360. / N as f32;
+        let mut node = DirectionNode::new(name, facing);
+        for (index, next) in directions.into_iter().enumerate() {
+            let center = index as f32 * width;
+            node = node.with_sector(center - width / 2., center + width / 2., next);
+        }
+        node
+    }
+
+    pub fn with_sector(mut self, from: f32, to: f32, next: NodeId<'static>) -> DirectionNode {
+        self.sectors.push(DirectionSector {
+            from,
+            to,
+            next,
+            flip_x: false,
+        });
+        self
+    }
+
+    /// a sector that shows `next` flipped on the x axis
+    pub fn with_mirrored_sector(
+        mut self,
+        from: f32,
+        to: f32,
+        next: NodeId<'static>,
+    ) -> DirectionNode {
+        self.sectors.push(DirectionSector {
+            from,
+            to,
+            next,
+            flip_x: true,
+        });
+        self
+    }
+
+    /// Keeps the picked sector in `hold` so it is used again while `facing` is `Vec2::ZERO`
+    pub fn with_hold(mut self, hold: Attribute) -> DirectionNode {
+        self.hold = Some(hold);
+        self
+    }
+
+    /// the angle of `facing` in degrees, `None` if it is a zero vector
+    fn degrees(&self, state: &AnimationState) -> Result<Option<f32>, RunError> {
+        if let Ok(facing) = state.get_attribute::<Vec2>(&self.facing) {
+            if *facing == Vec2::ZERO {
+                return Ok(None);
+            }
+            return Ok(Some(facing.y.atan2(facing.x).to_degrees()));
+        }
+        match state.get_attribute::<f32>(&self.facing) {
+            Ok(angle) => Ok(Some(angle.to_degrees())),
+            Err(e) => Err(RunError::Custom(format!(
+                "Direction: {}: {} must be a Vec2 or an f32 angle",
+                e, self.facing
+            ))),
+        }
+    }
+}
+
+impl AnimationNodeTrait for DirectionNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, state: &mut AnimationState) -> Result<NodeResult, RunError> {
+        let held = self
+            .hold
+            .as_ref()
+            .and_then(|hold| state.get_attribute::<usize>(hold).ok())
+            .copied()
+            .filter(|index| *index < self.sectors.len());
+        let index = match (self.degrees(state)?, held) {
+            (None, Some(held)) => held,
+            (degrees, _) => {
+                let degrees = degrees.unwrap_or_default();
+                let Some(index) = self
+                    .sectors
+                    .iter()
+                    .position(|sector| sector.contains(degrees))
+                else {
+                    return Err(RunError::Custom(format!(
+                        "Direction: {} has no sector for {} degrees",
+                        self.name, degrees
+                    )));
+                };
+                if let Some(hold) = &self.hold {
+                    if held != Some(index) {
+                        state.set_attribute(hold.clone(), index);
+                    }
+                }
+                index
+            }
+        };
+        let sector = &self.sectors[index];
+        if self.sectors.iter().any(|sector| sector.flip_x)
+            && state.get_attribute::<bool>(&Attribute::FlipX).ok() != Some(&sector.flip_x)
+        {
+            state.set_attribute(Attribute::FlipX, sector.flip_x);
+        }
+        Ok(NodeResult::Next(sector.next.to_static()))
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+    }
+
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        self.sectors
+            .iter()
+            .map(|sector| sector.next.to_static())
+            .collect()
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        out.push_str(&format!(" [label=\"{}\\n{}\"];\n", self.name, self.facing));
+        for sector in self.sectors.iter() {
+            this.dot(out);
+            out.push_str(" -> ");
+            sector.next.dot(out);
+            if sector.flip_x {
+                out.push_str(&format!(
+                    " [label=\"{}..{} flip\", style=dashed];\n",
+                    sector.from, sector.to
+                ));
+            } else {
+                out.push_str(&format!(" [label=\"{}..{}\"];\n", sector.from, sector.to));
+            }
+        }
+    }
+}
+
+#[test]
+fn direction_sectors() {
+    let facing = Attribute::new_attribute("Facing");
+    let node = DirectionNode::new("walk", facing.clone())
+        .with_sector(-45., 45., NodeId::U64(1))
+        .with_sector(45., 135., NodeId::U64(2))
+        .with_mirrored_sector(135., 225., NodeId::U64(1))
+        .with_sector(225., 315., NodeId::U64(3))
+        .with_hold(Attribute::new_index("Facing"));
+    let mut state = AnimationState::default();
+    let face = |state: &mut AnimationState, direction: Vec2| {
+        state.set_attribute(facing.clone(), direction);
+        let Ok(NodeResult::Next(NodeId::U64(next))) = node.run(state) else {panic!("DirectionNode did not pick a sector")};
+        (next, *state.attribute::<bool>(&Attribute::FlipX))
+    };
+    assert_eq!(face(&mut state, Vec2::new(1., 0.2)), (1, false));
+    assert_eq!(face(&mut state, Vec2::new(0., 1.)), (2, false));
+    assert_eq!(face(&mut state, Vec2::new(-1., -0.5)), (1, true));
+    assert_eq!(face(&mut state, Vec2::ZERO), (1, true));
+    assert_eq!(face(&mut state, Vec2::new(0.1, -1.)), (3, false));
+
+    let eight = DirectionNode::eight(
+        "eight",
+        facing.clone(),
+        std::array::from_fn(|index| NodeId::U64(index as u64)),
+    );
+    state.set_attribute(facing.clone(), std::f32::consts::FRAC_PI_4 * 5.);
+    assert!(matches!(
+        eight.run(&mut state),
+        Ok(NodeResult::Next(NodeId::U64(5)))
+    ));
+    state.set_attribute(facing, -0.1f32);
+    assert!(matches!(
+        eight.run(&mut state),
+        Ok(NodeResult::Next(NodeId::U64(0)))
+    ));
+
+    let loaded = ron::from_str::<DirectionNode>(
+        r#"DirectionNode(
+            name: "Walk",
+            facing: Custom("Facing"),
+            sectors: [(from: -45, to: 45, next: Id(1)), (from: 135, to: 225, next: Id(1), flip_x: true)],
+        )"#,
+    )
+    .unwrap();
+    assert_eq!(loaded.sectors.len(), 2);
+    assert!(loaded.sectors[1].flip_x);
+}
@@ -71,6 +71,10 @@ impl AnimationNodeTrait for FPSNode {
         self.id = Some(id.to_static());
     }
 
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        vec![self.then.to_static()]
+    }
+
     #[cfg(feature = "dot")]
     fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
         this.dot(out);
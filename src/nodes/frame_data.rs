@@ -0,0 +1,784 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+use bevy::asset::HandleId;
+use bevy::prelude::{Handle, Image, Vec2};
+use bevy::reflect::{FromReflect, Reflect, TypePath};
+use serde::Deserializer;
+
+use crate::error::LoadError;
+use crate::hitbox::{HitShape, Hitbox};
+use crate::nodes::index_atlas_node::AtlasLayout;
+use crate::prelude::*;
+use crate::utils::get_node_hash;
+
+/// A named point in a clip that will send an [`AnimationEvent`](crate::events::AnimationEvent)
+/// when the playback reaches `frame`
+#[derive(Debug, Clone, Reflect)]
+pub struct FrameMarker {
+    pub frame: usize,
+    pub name: Cow<'static, str>,
+}
+
+/// How far the entity should move when the playback reaches `frame`
+/// `offset` is in sprite-local space and `rotation` is in radians
+#[derive(Debug, Clone, Reflect)]
+pub struct FrameMotion {
+    pub frame: usize,
+    pub offset: Vec2,
+    pub rotation: f32,
+}
+
+/// A hitbox that is active while `frame` is showing
+#[derive(Debug, Clone, Reflect)]
+pub struct FrameHitbox {
+    pub frame: usize,
+    pub hitbox: Hitbox,
+}
+
+/// Clips in the same sync group keep there normalized progress when the animation switches between them
+/// so a walk can turn into a run without the feet popping
+#[derive(Debug, Clone, Reflect)]
+pub struct SyncGroup {
+    id: u64,
+    name: Cow<'static, str>,
+}
+
+impl SyncGroup {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> SyncGroup {
+        let name = name.into();
+        SyncGroup {
+            id: get_node_hash(&name),
+            name,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The last clip to run in a sync group and its normalized progress
+#[derive(Debug, Clone)]
+pub(crate) struct SyncProgress {
+    pub(crate) node: NodeId<'static>,
+    pub(crate) progress: f32,
+    /// if a clip in the group ran since changes were last cleared, groups that did not run are forgotten
+    pub(crate) ran: bool,
+}
+
+/// Attributes a clip sets every update so other nodes can react to it without knowing how many frames it has
+#[derive(Debug, Default, Clone, PartialEq, Reflect)]
+pub struct ClipAttributes {
+    /// `bool` that is true on the update the clip reached its end, or finished a loop if it never ends
+    /// it only lives for a frame so it is not left set to true when the clip stops running
+    pub finished: Option<Attribute>,
+    /// `f32` from `0.0` to `1.0`, how far through the clip the playback is
+    pub progress: Option<Attribute>,
+    /// `usize` number of times the playback has got to the end of the clip since it started
+    pub loops: Option<Attribute>,
+}
+
+impl ClipAttributes {
+    fn is_empty(&self) -> bool {
+        self.finished.is_none() && self.progress.is_none() && self.loops.is_none()
+    }
+}
+
+/// Extra data that is attached to a clip node and its individual frames
+#[derive(Debug, Default, Reflect)]
+pub struct FrameData {
+    markers: Vec<FrameMarker>,
+    motion: Vec<FrameMotion>,
+    hitboxes: Vec<FrameHitbox>,
+    sync_group: Option<SyncGroup>,
+    clip: ClipAttributes,
+}
+
+impl FrameData {
+    pub fn add_marker(&mut self, frame: usize, name: impl Into<Cow<'static, str>>) {
+        self.markers.push(FrameMarker {
+            frame,
+            name: name.into(),
+        });
+    }
+
+    pub fn markers(&self) -> &[FrameMarker] {
+        &self.markers
+    }
+
+    pub fn add_motion(&mut self, frame: usize, offset: Vec2, rotation: f32) {
+        self.motion.push(FrameMotion {
+            frame,
+            offset,
+            rotation,
+        });
+    }
+
+    pub fn motion(&self) -> &[FrameMotion] {
+        &self.motion
+    }
+
+    pub fn add_hitbox(
+        &mut self,
+        frame: usize,
+        name: impl Into<Cow<'static, str>>,
+        shape: HitShape,
+    ) {
+        self.hitboxes.push(FrameHitbox {
+            frame,
+            hitbox: Hitbox {
+                name: name.into(),
+                shape,
+            },
+        });
+    }
+
+    pub fn hitboxes(&self) -> &[FrameHitbox] {
+        &self.hitboxes
+    }
+
+    /// Called by a clip with the frame it will show after it has updated
+    pub fn show_frame(&self, frame: usize, state: &mut AnimationState) {
+        for hitbox in self.hitboxes.iter().filter(|h| h.frame == frame) {
+            state.hitboxes.push(hitbox.hitbox.clone());
+        }
+    }
+
+    pub fn set_sync_group(&mut self, group: impl Into<Cow<'static, str>>) {
+        self.sync_group = Some(SyncGroup::new(group));
+    }
+
+    pub fn sync_group(&self) -> Option<&SyncGroup> {
+        self.sync_group.as_ref()
+    }
+
+    /// If another clip in the same sync group was the last one to run
+    /// returns the index in this clip at the same normalized progress
+    pub fn sync_index(
+        &self,
+        node: &NodeId<'_>,
+        len: usize,
+        state: &AnimationState,
+    ) -> Option<usize> {
+        let group = self.sync_group.as_ref()?;
+        let sync = state.sync.get(&group.id)?;
+        if sync.node == *node {
+            return None;
+        }
+        // the small offset stops 0.999 from rounding down a whole frame
+        Some((((sync.progress * len as f32) + 0.0001).floor() as usize).min(len - 1))
+    }
+
+    pub fn clip_attributes(&self) -> &ClipAttributes {
+        &self.clip
+    }
+
+    pub fn clip_attributes_mut(&mut self) -> &mut ClipAttributes {
+        &mut self.clip
+    }
+
+    /// Called by a clip after it has moved `steps` positions from `from`
+    /// `restarted` is true if the clip was not playing last update
+    pub fn publish_clip(
+        &self,
+        mode: LoopMode,
+        len: usize,
+        from: usize,
+        steps: usize,
+        restarted: bool,
+        state: &mut AnimationState,
+    ) {
+        if self.clip.is_empty() {
+            return;
+        }
+        let position = mode.wrap(from + steps, len);
+        let cycles = mode.cycles(from, steps, len);
+        if let Some(finished) = &self.clip.finished {
+            let finished_now = match mode.length(len) {
+                Some(length) => (steps > 0 || restarted) && position == length - 1,
+                None => cycles > 0,
+            };
+            state.set_attribute_for(finished.clone(), finished_now, Lifetime::FRAME);
+        }
+        if let Some(progress) = &self.clip.progress {
+            state.set_attribute(progress.clone(), mode.progress(position, len));
+        }
+        if let Some(loops) = &self.clip.loops {
+            let last = if restarted {
+                0
+            } else {
+                state
+                    .get_attribute::<usize>(loops)
+                    .copied()
+                    .unwrap_or_default()
+            };
+            state.set_attribute(loops.clone(), last + cycles);
+        }
+    }
+
+    /// Records the normalized progress of this clip for the next clip in its sync group
+    pub fn store_sync(
+        &self,
+        node: &NodeId<'_>,
+        index: usize,
+        len: usize,
+        state: &mut AnimationState,
+    ) {
+        if let Some(group) = &self.sync_group {
+            state.sync.insert(
+                group.id,
+                SyncProgress {
+                    node: node.to_static(),
+                    progress: index as f32 / len as f32,
+                    ran: true,
+                },
+            );
+        }
+    }
+
+    /// returns true if there is nothing that needs to know what frames were passed
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty() && self.motion.is_empty()
+    }
+
+    /// Called by a clip for every frame the playback enters during an update
+    pub fn enter_frame(&self, node: &NodeId<'_>, frame: usize, state: &mut AnimationState) {
+        for marker in self.markers.iter().filter(|m| m.frame == frame) {
+            state.emit_marker(node.to_static(), marker.name.clone(), frame);
+        }
+        for motion in self.motion.iter().filter(|m| m.frame == frame) {
+            state.add_root_motion(motion.offset, motion.rotation);
+        }
+    }
+}
+
+impl From<Vec<(usize, String)>> for FrameData {
+    fn from(markers: Vec<(usize, String)>) -> Self {
+        let mut data = FrameData::default();
+        for (frame, name) in markers {
+            data.add_marker(frame, name);
+        }
+        data
+    }
+}
+
+impl FrameData {
+    /// builds the frame data of a clip from the fields of a `.nodetree`
+    pub(crate) fn from_fields(
+        markers: Vec<(usize, String)>,
+        motion: Vec<(usize, Vec2, f32)>,
+        hitboxes: Vec<(usize, String, HitShape)>,
+        sync: Option<String>,
+        clip: ClipAttributes,
+    ) -> FrameData {
+        let mut data = FrameData::from(markers);
+        for (frame, offset, rotation) in motion {
+            data.add_motion(frame, offset, rotation);
+        }
+        for (frame, name, shape) in hitboxes {
+            data.add_hitbox(frame, name, shape);
+        }
+        if let Some(sync) = sync {
+            data.set_sync_group(sync);
+        }
+        data.clip = clip;
+        data
+    }
+}
+
+/// How a clip plays its frames
+/// the index attribute of the clip is how far the clip has played, for `Once` and `Loop` that is the frame shown
+/// for the other modes use [`LoopMode::frame`] to get the frame shown
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, serde::Deserialize, serde::Serialize,
+)]
+pub enum LoopMode {
+    /// play the frames once and hold the last frame
+    #[default]
+    Once,
+    /// play the frames over and over
+    Loop,
+    /// play the frames forward then backward over and over, the first and last frames are not shown twice
+    PingPong,
+    /// play the frames once from last to first and hold the first frame
+    Reverse,
+    /// play the frames this many times then hold the last frame
+    Times(usize),
+}
+
+impl From<bool> for LoopMode {
+    /// `true` is `Loop` and `false` is `Once` like the old `is_loop`
+    fn from(is_loop: bool) -> Self {
+        if is_loop {
+            LoopMode::Loop
+        } else {
+            LoopMode::Once
+        }
+    }
+}
+
+impl LoopMode {
+    /// the number of positions before the clip ends, `None` if it never ends
+    pub fn length(&self, len: usize) -> Option<usize> {
+        match self {
+            LoopMode::Once | LoopMode::Reverse => Some(len),
+            LoopMode::Times(times) => Some(len * (*times).max(1)),
+            LoopMode::Loop | LoopMode::PingPong => None,
+        }
+    }
+
+    /// the number of positions in one play through of the clip
+    pub fn cycle(&self, len: usize) -> usize {
+        match self {
+            LoopMode::PingPong => (len * 2).saturating_sub(2).max(1),
+            _ => len,
+        }
+    }
+
+    /// the frame shown at `position`
+    pub fn frame(&self, position: usize, len: usize) -> usize {
+        let position = self.wrap(position, len);
+        match self {
+            LoopMode::Once | LoopMode::Loop | LoopMode::Times(_) => position % len,
+            LoopMode::Reverse => len - 1 - position,
+            LoopMode::PingPong => {
+                let cycle = self.cycle(len);
+                if position < len {
+                    position
+                } else {
+                    cycle - position
+                }
+            }
+        }
+    }
+
+    /// Puts a position that is past the end of a clip back into the clip
+    #[inline(always)]
+    pub fn wrap(&self, position: usize, len: usize) -> usize {
+        match self.length(len) {
+            Some(length) => position.min(length - 1),
+            None => position % self.cycle(len),
+        }
+    }
+
+    /// how many of `frames` the playback can move from `position` before the clip ends
+    pub fn steps(&self, position: usize, frames: usize, len: usize) -> usize {
+        match self.length(len) {
+            Some(length) => frames.min((length - 1).saturating_sub(position)),
+            None => frames,
+        }
+    }
+
+    /// how many times the playback got to the end of a cycle moving `steps` positions from `position`
+    pub fn cycles(&self, position: usize, steps: usize, len: usize) -> usize {
+        let cycle = self.cycle(len);
+        (position + steps + 1) / cycle - (position + 1) / cycle
+    }
+
+    /// how far through the clip `position` is from `0.0` to `1.0`
+    /// clips that never end go back to `0.0` at the start of each cycle
+    pub fn progress(&self, position: usize, len: usize) -> f32 {
+        let (position, last) = match self.length(len) {
+            Some(length) => (position.min(length - 1), length - 1),
+            None => (position % self.cycle(len), self.cycle(len) - 1),
+        };
+        if last == 0 {
+            1.0
+        } else {
+            position as f32 / last as f32
+        }
+    }
+
+    /// The position after `position`; `None` if the clip has ended
+    #[inline(always)]
+    pub fn next(&self, position: usize, len: usize) -> Option<usize> {
+        match self.length(len) {
+            Some(length) if position + 1 >= length => None,
+            _ => Some(self.wrap(position + 1, len)),
+        }
+    }
+}
+
+/// A frame of a [`Clip`], either an image or the index of a sprite in an atlas
+/// a frame with a duration is shown for that many seconds, without one it is shown for one of [`Attribute::Frames`]
+pub trait ClipFrame: FromReflect + TypePath + Clone + std::fmt::Debug {
+    fn duration(&self) -> Option<f32> {
+        None
+    }
+}
+
+impl ClipFrame for Handle<Image> {}
+
+impl ClipFrame for usize {}
+
+impl<F: ClipFrame> ClipFrame for (F, f32) {
+    fn duration(&self) -> Option<f32> {
+        Some(self.1)
+    }
+}
+
+/// The frames of a clip node and how they are played
+/// the node only has to turn the frame [`Clip::run`] returns into its output
+#[derive(Debug, Reflect)]
+pub struct Clip<F: ClipFrame> {
+    frames: Vec<F>,
+    mode: LoopMode,
+    index: Attribute,
+    frame_data: FrameData,
+    /// where the node that last played `index` is kept, clips that share an index start over when the node changes
+    #[reflect(ignore)]
+    owner: OnceLock<Attribute>,
+}
+
+impl<F: ClipFrame> Clip<F> {
+    pub fn new(frames: Vec<F>, mode: LoopMode, index: Attribute) -> Clip<F> {
+        Clip {
+            frames,
+            mode,
+            index,
+            frame_data: FrameData::default(),
+            owner: OnceLock::new(),
+        }
+    }
+
+    pub fn frames(&self) -> &[F] {
+        &self.frames
+    }
+
+    pub fn mode(&self) -> LoopMode {
+        self.mode
+    }
+
+    pub fn index(&self) -> &Attribute {
+        &self.index
+    }
+
+    pub fn frame_data(&self) -> &FrameData {
+        &self.frame_data
+    }
+
+    pub fn frame_data_mut(&mut self) -> &mut FrameData {
+        &mut self.frame_data
+    }
+
+    /// Plays the clip for this update and returns the frame to show
+    /// `node` is the id of the node playing the clip, it is used for markers and sync groups
+    pub fn run(&self, node: NodeId<'_>, state: &mut AnimationState) -> &F {
+        assert!(!self.frames.is_empty());
+        let len = self.frames.len();
+        let mode = self.mode;
+        let owner = self
+            .owner
+            .get_or_init(|| Attribute::new_index_id(&self.index));
+        let player = get_node_hash(&HandleId::from(&node)) as usize;
+        let last_player = state.get_attribute::<usize>(owner).ok().copied();
+        let mut started =
+            state.get_attribute::<usize>(&self.index).is_ok() && last_player == Some(player);
+        let mut index = state.index(&self.index);
+        let cycle = mode.cycle(len);
+        let synced = self.frame_data.sync_index(&node, cycle, state);
+        if let Some(synced) = synced {
+            index = synced;
+            started = false;
+        }
+        let frames = *state.attribute::<usize>(&Attribute::Frames);
+        let mut index = mode.wrap(index, len);
+        let from = index;
+        let mut steps = 0;
+        // only clips with markers or motion need to step one frame at a time so no marked frame is skipped
+        let marked = (!self.frame_data.is_empty()).then_some(&node);
+        // a clip that jumped to the progress of its sync group did not reach the frame it landed on
+        if let (Some(node), false) = (marked, started || synced.is_some()) {
+            self.frame_data
+                .enter_frame(node, mode.frame(index, len), state);
+        }
+        if self.frames[0].duration().is_some() {
+            let rem_time = *state.attribute::<f32>(&Attribute::TimeThisFrame);
+            let mut frame_time =
+                state.attribute::<f32>(&Attribute::LastFPS) * frames as f32 + rem_time;
+            while let Some(duration) = self.frames[mode.frame(index, len)]
+                .duration()
+                .filter(|duration| frame_time > *duration)
+            {
+                frame_time -= duration;
+                if self.advance(&mut index, marked, state) {
+                    steps += 1;
+                }
+            }
+            state.set_attribute(Attribute::TimeThisFrame, frame_time);
+        } else if marked.is_none() {
+            steps = mode.steps(index, frames, len);
+            index = mode.wrap(index + steps, len);
+        } else {
+            while steps < frames && self.advance(&mut index, marked, state) {
+                steps += 1;
+            }
+        }
+        self.frame_data
+            .publish_clip(mode, len, from, steps, !started, state);
+        let frame = mode.frame(index, len);
+        self.frame_data.show_frame(frame, state);
+        self.frame_data
+            .store_sync(&node, index % cycle, cycle, state);
+        state.set_attribute(self.index.clone(), index);
+        if last_player != Some(player) {
+            state.set_attribute(owner.clone(), player);
+        }
+        &self.frames[frame]
+    }
+
+    /// moves the playback one position, returns false if the clip has ended
+    fn advance(
+        &self,
+        index: &mut usize,
+        marked: Option<&NodeId<'_>>,
+        state: &mut AnimationState,
+    ) -> bool {
+        let len = self.frames.len();
+        let Some(next) = self.mode.next(*index, len) else {return false;};
+        *index = next;
+        if let Some(node) = marked {
+            self.frame_data
+                .enter_frame(node, self.mode.frame(next, len), state);
+        }
+        true
+    }
+}
+
+/// The builders shared by every node that plays a [`Clip`]
+pub trait ClipNode: Sized {
+    fn frame_data_mut(&mut self) -> &mut FrameData;
+
+    /// Adds a marker that will send an [`AnimationEvent`](crate::events::AnimationEvent) every time `frame` is reached
+    fn with_marker(mut self, frame: usize, marker: impl Into<Cow<'static, str>>) -> Self {
+        self.frame_data_mut().add_marker(frame, marker);
+        self
+    }
+
+    /// Moves the entity by `offset` and turns it by `rotation` radians every time `frame` is reached
+    fn with_motion(mut self, frame: usize, offset: Vec2, rotation: f32) -> Self {
+        self.frame_data_mut().add_motion(frame, offset, rotation);
+        self
+    }
+
+    /// Adds a hitbox called `name` that is active while `frame` is showing
+    fn with_hitbox(
+        mut self,
+        frame: usize,
+        name: impl Into<Cow<'static, str>>,
+        shape: HitShape,
+    ) -> Self {
+        self.frame_data_mut().add_hitbox(frame, name, shape);
+        self
+    }
+
+    /// When the animation switches to this clip from another clip in `group` it will start at the same normalized progress
+    fn with_sync_group(mut self, group: impl Into<Cow<'static, str>>) -> Self {
+        self.frame_data_mut().set_sync_group(group);
+        self
+    }
+
+    /// Sets `finished` to true on the update this clip reaches its end
+    fn with_finished(mut self, finished: Attribute) -> Self {
+        self.frame_data_mut().clip_attributes_mut().finished = Some(finished);
+        self
+    }
+
+    /// Sets `progress` to how far through this clip the playback is from `0.0` to `1.0`
+    fn with_progress(mut self, progress: Attribute) -> Self {
+        self.frame_data_mut().clip_attributes_mut().progress = Some(progress);
+        self
+    }
+
+    /// Sets `loops` to the number of times the playback got to the end of this clip since it started
+    fn with_loops(mut self, loops: Attribute) -> Self {
+        self.frame_data_mut().clip_attributes_mut().loops = Some(loops);
+        self
+    }
+}
+
+/// The fields of a clip node in a `.nodetree`, `F` is how the frames are writen
+pub(crate) struct ClipFields<F> {
+    pub(crate) name: String,
+    pub(crate) atlas: Option<AtlasLayout>,
+    frames: Vec<F>,
+    mode: LoopMode,
+    index: Attribute,
+    frame_data: FrameData,
+}
+
+impl<F: serde::de::DeserializeOwned> ClipFields<F> {
+    /// `atlas` is true for nodes that pick there frames out of a `TextureAtlas`
+    pub(crate) fn from_ron(
+        s: &str,
+        node: &'static str,
+        atlas: bool,
+    ) -> Result<ClipFields<F>, LoadError> {
+        let mut de = ron::de::Deserializer::from_str(s)?;
+        match de.deserialize_struct(
+            node,
+            &[],
+            ClipLoader {
+                node,
+                atlas,
+                frames: PhantomData,
+            },
+        ) {
+            Ok(ok) => Ok(ok),
+            Err(e) => Err(LoadError::Ron(ron::de::SpannedError {
+                code: e,
+                position: ron::de::Position { line: 0, col: 0 },
+            })),
+        }
+    }
+}
+
+impl<F> ClipFields<F> {
+    /// the clip with each frame turned into what the node shows
+    pub(crate) fn into_clip<T: ClipFrame>(self, frame: impl FnMut(F) -> T) -> Clip<T> {
+        Clip {
+            frames: self.frames.into_iter().map(frame).collect(),
+            mode: self.mode,
+            index: self.index,
+            frame_data: self.frame_data,
+            owner: OnceLock::new(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Fileds {
+    Name,
+    Atlas,
+    Frames,
+    IsLoop,
+    LoopMode,
+    Index,
+    Markers,
+    Motion,
+    Hitboxes,
+    Sync,
+    Finished,
+    Progress,
+    Loops,
+}
+
+struct ClipLoader<F> {
+    node: &'static str,
+    atlas: bool,
+    frames: PhantomData<F>,
+}
+
+impl<'de, F: serde::de::DeserializeOwned> serde::de::Visitor<'de> for ClipLoader<F> {
+    type Value = ClipFields<F>;
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "Ron String or a {}", self.node)
+    }
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+        let mut name = None;
+        let mut atlas = None;
+        let mut frames = None;
+        let mut mode = LoopMode::Once;
+        let mut index = Attribute::IndexId(0);
+        let mut markers = Vec::new();
+        let mut motion = Vec::new();
+        let mut hitboxes = Vec::new();
+        let mut sync = None;
+        let mut clip = ClipAttributes::default();
+        while let Some(key) = map.next_key::<Fileds>()? {
+            match key {
+                Fileds::Name => name = Some(map.next_value::<String>()?),
+                Fileds::Atlas if self.atlas => atlas = Some(map.next_value::<AtlasLayout>()?),
+                Fileds::Atlas => return Err(Error::unknown_field("atlas", &[])),
+                Fileds::Frames => frames = Some(map.next_value::<Vec<F>>()?),
+                Fileds::IsLoop => mode = map.next_value::<bool>()?.into(),
+                Fileds::LoopMode => mode = map.next_value::<LoopMode>()?,
+                Fileds::Index => index = map.next_value::<Attribute>()?,
+                Fileds::Markers => markers = map.next_value::<Vec<(usize, String)>>()?,
+                Fileds::Motion => motion = map.next_value::<Vec<(usize, Vec2, f32)>>()?,
+                Fileds::Hitboxes => {
+                    hitboxes = map.next_value::<Vec<(usize, String, HitShape)>>()?
+                }
+                Fileds::Sync => sync = Some(map.next_value::<String>()?),
+                Fileds::Finished => clip.finished = Some(map.next_value::<Attribute>()?),
+                Fileds::Progress => clip.progress = Some(map.next_value::<Attribute>()?),
+                Fileds::Loops => clip.loops = Some(map.next_value::<Attribute>()?),
+            }
+        }
+        if self.atlas && atlas.is_none() {
+            return Err(Error::missing_field("Atlas"));
+        }
+        let Some(frames) = frames else {return Err(Error::missing_field("Frames"));};
+        let Some(name) = name else {return Err(Error::missing_field("Name"));};
+        Ok(ClipFields {
+            name,
+            atlas,
+            frames,
+            mode,
+            index,
+            frame_data: FrameData::from_fields(markers, motion, hitboxes, sync, clip),
+        })
+    }
+}
+
+#[test]
+fn loop_mode_frames() {
+    let frames = |mode: LoopMode| {
+        (0..10)
+            .map(|position| mode.frame(position, 4))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(frames(LoopMode::Once), [0, 1, 2, 3, 3, 3, 3, 3, 3, 3]);
+    assert_eq!(frames(LoopMode::Loop), [0, 1, 2, 3, 0, 1, 2, 3, 0, 1]);
+    assert_eq!(frames(LoopMode::PingPong), [0, 1, 2, 3, 2, 1, 0, 1, 2, 3]);
+    assert_eq!(frames(LoopMode::Reverse), [3, 2, 1, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(frames(LoopMode::Times(2)), [0, 1, 2, 3, 0, 1, 2, 3, 3, 3]);
+    assert_eq!(LoopMode::PingPong.frame(0, 1), 0);
+    assert_eq!(LoopMode::Times(2).next(7, 4), None);
+    assert_eq!(LoopMode::Loop.next(3, 4), Some(0));
+    assert_eq!(LoopMode::from(true), LoopMode::Loop);
+    assert_eq!(LoopMode::Once.steps(2, 5, 4), 1);
+    assert_eq!(LoopMode::Loop.cycles(2, 6, 4), 2);
+    assert_eq!(LoopMode::Times(2).cycles(0, 7, 4), 2);
+    assert_eq!(LoopMode::Loop.progress(6, 4), 2.0 / 3.0);
+}
+
+#[test]
+fn clip_fields_and_timing() {
+    let fields = ClipFields::<(usize, f32)>::from_ron(
+        r#"(name: "walk", frames: [(0, 0.1), (1, 0.2)], loopmode: Loop, finished: Custom("WalkDone"))"#,
+        "VariableAtlasNode",
+        false,
+    )
+    .unwrap();
+    assert_eq!(fields.name, "walk");
+    let clip = fields.into_clip(|frame| frame);
+    assert_eq!(clip.mode(), LoopMode::Loop);
+    assert!(clip.frame_data().clip_attributes().finished.is_some());
+    assert!(ClipFields::<usize>::from_ron(
+        r#"(name: "walk", frames: [0])"#,
+        "IndexAtlasNode",
+        true
+    )
+    .is_err());
+    assert!(ClipFields::<usize>::from_ron(
+        r#"(name: "walk", frames: [0], atlas: (texture: "a.png", tile_size: (1., 1.), columns: 1, rows: 1))"#,
+        "IndexNode",
+        false
+    )
+    .is_err());
+
+    let mut state = AnimationState::default();
+    state.set_attribute(Attribute::LastFPS, 0.1f32);
+    state.set_attribute(Attribute::TimeThisFrame, 0.0f32);
+    state.set_attribute(Attribute::Frames, 2usize);
+    assert_eq!(*clip.run(NodeId::U64(1), &mut state), (1, 0.2));
+    assert!((state.attribute::<f32>(&Attribute::TimeThisFrame) - 0.1).abs() < 0.0001);
+}
@@ -0,0 +1,160 @@
+use crate::nodes::frame_data::{Clip, ClipFields, ClipNode, FrameData, LoopMode};
+use crate::prelude::*;
+use crate::serde::LoadNode;
+use crate::serde::ReflectLoadNode;
+use bevy::asset::AssetPath;
+use bevy::asset::LoadedAsset;
+use bevy::prelude::Handle;
+use bevy::prelude::Image;
+use bevy::prelude::Vec2;
+use bevy::reflect::Reflect;
+use bevy::sprite::TextureAtlas;
+
+/// Works like an [`IndexNode`] but picks frames out of a `TextureAtlas`
+/// the frames are the indexes of the sprites in the atlas in the order they should play
+#[derive(Debug, Reflect)]
+#[reflect(LoadNode)]
+pub struct IndexAtlasNode {
+    id: Option<NodeId<'static>>,
+    name: String,
+    atlas: Handle<TextureAtlas>,
+    clip: Clip<usize>,
+}
+
+impl IndexAtlasNode {
+    #[inline(always)]
+    pub fn new(
+        name: &str,
+        atlas: Handle<TextureAtlas>,
+        frames: &[usize],
+        mode: impl Into<LoopMode>,
+    ) -> IndexAtlasNode {
+        IndexAtlasNode::new_with_index(name, atlas, frames, mode, Attribute::IndexId(0))
+    }
+
+    #[inline(always)]
+    pub fn new_with_index(
+        name: &str,
+        atlas: Handle<TextureAtlas>,
+        frames: &[usize],
+        mode: impl Into<LoopMode>,
+        index: Attribute,
+    ) -> IndexAtlasNode {
+        IndexAtlasNode {
+            id: None,
+            name: name.to_string(),
+            atlas,
+            clip: Clip::new(frames.to_vec(), mode.into(), index),
+        }
+    }
+}
+
+impl ClipNode for IndexAtlasNode {
+    fn frame_data_mut(&mut self) -> &mut FrameData {
+        self.clip.frame_data_mut()
+    }
+}
+
+impl AnimationNodeTrait for IndexAtlasNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, state: &mut AnimationState) -> Result<NodeResult, RunError> {
+        let frame = *self.clip.run(self.id(), state);
+        Ok(NodeResult::DoneAtlas(self.atlas.clone(), frame))
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, asset_server: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        out.push_str(&format!(" [label=\"{}\"];\n", self.name));
+        this.dot(out);
+        out.push_str(" -> ");
+        let h = handle_to_node(self.atlas.id());
+        h.dot(out);
+        out.push_str(&format!(" [label=\"{:?}\"];\n", self.clip.frames()));
+        if let Some(path) = asset_server.get_handle_path(&self.atlas) {
+            h.dot(out);
+            out.push_str(&format!(
+                " [label=\"{}\"];\n",
+                path.label().unwrap_or("atlas")
+            ));
+            h.dot(out);
+            out.push_str(" [color=green];\n");
+        }
+    }
+}
+
+/// The layout of a grid based `TextureAtlas` as it is writen in a `.nodetree`
+/// ```ron
+/// atlas: (
+///     texture: "Zombie1.png",
+///     tile_size: (430., 519.),
+///     columns: 8,
+///     rows: 9,
+/// ),
+/// ```
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct AtlasLayout {
+    texture: String,
+    tile_size: Vec2,
+    columns: usize,
+    rows: usize,
+    #[serde(default)]
+    padding: Option<Vec2>,
+    #[serde(default)]
+    offset: Option<Vec2>,
+}
+
+impl AtlasLayout {
+    /// Adds the atlas as a labeled asset of the file being loaded, the label is `{node name}_atlas`
+    pub(crate) fn load(
+        self,
+        node: &str,
+        load_context: &mut bevy::asset::LoadContext,
+        dependencies: &mut Vec<AssetPath<'static>>,
+    ) -> Handle<TextureAtlas> {
+        let texture = load_context.get_handle::<_, Image>(&self.texture);
+        dependencies.push(self.texture.into());
+        let atlas = TextureAtlas::from_grid(
+            texture,
+            self.tile_size,
+            self.columns,
+            self.rows,
+            self.padding,
+            self.offset,
+        );
+        load_context.set_labeled_asset(&format!("{}_atlas", node), LoadedAsset::new(atlas))
+    }
+}
+
+impl LoadNode for IndexAtlasNode {
+    fn load<'b>(
+        s: &str,
+        load_context: &mut bevy::asset::LoadContext<'b>,
+        dependencies: &mut Vec<AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let mut fields = ClipFields::<usize>::from_ron(s, "IndexAtlasNode", true)?;
+        let Some(atlas) = fields.atlas.take() else {unreachable!("ClipFields checks atlas nodes have an atlas")};
+        let atlas = atlas.load(&fields.name, load_context, dependencies);
+        Ok(AnimationNode::new(IndexAtlasNode {
+            id: None,
+            name: fields.name.clone(),
+            atlas,
+            clip: fields.into_clip(|frame| frame),
+        }))
+    }
+}
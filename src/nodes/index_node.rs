@@ -1,4 +1,4 @@
-use crate::error::LoadError;
+use crate::nodes::frame_data::{Clip, ClipFields, ClipNode, FrameData, LoopMode};
 use crate::prelude::*;
 use crate::serde::LoadNode;
 use crate::serde::ReflectLoadNode;
@@ -6,66 +6,49 @@ use bevy::asset::AssetPath;
 use bevy::prelude::Handle;
 use bevy::prelude::Image;
 use bevy::reflect::Reflect;
-use serde::Deserializer;
 
 #[derive(Debug, Reflect)]
 #[reflect(LoadNode)]
 pub struct IndexNode {
     id: Option<NodeId<'static>>,
     name: String,
-    frames: Vec<Handle<Image>>,
-    is_loop: bool,
-    index: Attribute,
+    clip: Clip<Handle<Image>>,
 }
 
 impl IndexNode {
     #[inline(always)]
-    pub fn new(name: &str, frames: &[Handle<Image>], is_loop: bool) -> IndexNode {
-        IndexNode {
-            id: None,
-            name: name.to_string(),
-            frames: frames.to_vec(),
-            is_loop,
-            index: Attribute::IndexId(0),
-        }
+    pub fn new(name: &str, frames: &[Handle<Image>], mode: impl Into<LoopMode>) -> IndexNode {
+        IndexNode::new_with_index(name, frames, mode, Attribute::IndexId(0))
     }
 
     #[inline(always)]
     pub fn new_with_index(
         name: &str,
         frames: &[Handle<Image>],
-        is_loop: bool,
+        mode: impl Into<LoopMode>,
         index: Attribute,
     ) -> IndexNode {
         IndexNode {
             id: None,
             name: name.to_string(),
-            frames: frames.to_vec(),
-            is_loop,
-            index,
+            clip: Clip::new(frames.to_vec(), mode.into(), index),
         }
     }
 }
 
+impl ClipNode for IndexNode {
+    fn frame_data_mut(&mut self) -> &mut FrameData {
+        self.clip.frame_data_mut()
+    }
+}
+
 impl AnimationNodeTrait for IndexNode {
     fn name(&self) -> &str {
         &self.name
     }
 
     fn run(&self, state: &mut AnimationState) -> Result<NodeResult, RunError> {
-        assert!(self.frames.len() != 0);
-        let mut index = state.index(&self.index);
-        let frames = state.attribute::<usize>(&Attribute::Frames);
-        index += frames;
-        if index >= self.frames.len() {
-            if self.is_loop {
-                index %= self.frames.len();
-            } else {
-                index = self.frames.len() - 1;
-            }
-        }
-        state.set_attribute(self.index.clone(), index);
-        Ok(NodeResult::Done(self.frames[index].clone()))
+        Ok(NodeResult::Done(self.clip.run(self.id(), state).clone()))
     }
 
     fn id(&self) -> NodeId {
@@ -84,7 +67,7 @@ impl AnimationNodeTrait for IndexNode {
     fn dot(&self, this: NodeId<'_>, out: &mut String, asset_server: &bevy::prelude::AssetServer) {
         this.dot(out);
         out.push_str(&format!(" [label=\"{}\"];\n", self.name));
-        for (i, index) in self.frames.iter().enumerate() {
+        for (i, index) in self.clip.frames().iter().enumerate() {
             this.dot(out);
             out.push_str(" -> ");
             let h = handle_to_node(index.id());
@@ -106,66 +89,103 @@ impl LoadNode for IndexNode {
         load_context: &mut bevy::asset::LoadContext<'b>,
         dependencies: &mut Vec<AssetPath<'static>>,
     ) -> Result<AnimationNode, crate::error::LoadError> {
-        let mut node = ron::de::Deserializer::from_str(s)?;
-        match node.deserialize_struct("IndexNode", &[], IndexLoader(load_context, dependencies)) {
-            Ok(ok) => Ok(AnimationNode::new(ok)),
-            Err(e) => Err(LoadError::Ron(ron::de::SpannedError {
-                code: e,
-                position: ron::de::Position { line: 0, col: 0 },
-            })),
-        }
+        let fields = ClipFields::<String>::from_ron(s, "IndexNode", false)?;
+        let name = fields.name.clone();
+        let clip = fields.into_clip(|frame| {
+            let handle = load_context.get_handle::<_, Image>(&frame);
+            dependencies.push(frame.into());
+            handle
+        });
+        Ok(AnimationNode::new(IndexNode {
+            id: None,
+            name,
+            clip,
+        }))
     }
 }
 
-#[derive(serde::Deserialize)]
-#[serde(field_identifier, rename_all = "lowercase")]
-enum Fileds {
-    Name,
-    Frames,
-    IsLoop,
-    Index,
+#[test]
+fn markers_on_skipped_frames() {
+    let node = IndexNode::new("test", &vec![Handle::default(); 4], true).with_marker(1, "step");
+    let mut state = AnimationState::default();
+    state.set_attribute(Attribute::IndexId(0), 0usize);
+    state.set_attribute(Attribute::Frames, 6usize);
+    node.run(&mut state).unwrap();
+    assert_eq!(state.index(&Attribute::IndexId(0)), 2);
+    let markers = state.markers().collect::<Vec<_>>();
+    assert_eq!(markers.len(), 2);
+    assert!(markers
+        .iter()
+        .all(|(_, marker, frame)| *marker == "step" && *frame == 1));
 }
 
-struct IndexLoader<'de, 'b: 'de>(
-    &'de mut bevy::asset::LoadContext<'b>,
-    &'de mut Vec<AssetPath<'static>>,
-);
+#[test]
+fn sync_group_keeps_progress() {
+    let walk = IndexNode::new("walk", &vec![Handle::default(); 12], true).with_sync_group("feet");
+    let run = IndexNode::new("run", &vec![Handle::default(); 8], true).with_sync_group("feet");
+    let mut state = AnimationState::default();
+    state.set_attribute(Attribute::IndexId(0), 6usize);
+    state.set_attribute(Attribute::Frames, 0usize);
+    walk.run(&mut state).unwrap();
+    run.run(&mut state).unwrap();
+    assert_eq!(state.index(&Attribute::IndexId(0)), 4);
+    state.set_attribute(Attribute::Frames, 1usize);
+    run.run(&mut state).unwrap();
+    assert_eq!(state.index(&Attribute::IndexId(0)), 5);
 
-impl<'de, 'b: 'de> serde::de::Visitor<'de> for IndexLoader<'de, 'b> {
-    type Value = IndexNode;
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("Ron String or a IndexNode")
-    }
-    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-    where
-        A: serde::de::MapAccess<'de>,
-    {
-        use serde::de::Error;
-        let mut name = None;
-        let mut frames = None;
-        let mut is_loop = false;
-        let mut index = Attribute::IndexId(0);
-        while let Some(key) = map.next_key::<Fileds>()? {
-            match key {
-                Fileds::Name => name = Some(map.next_value::<String>()?),
-                Fileds::Frames => frames = Some(map.next_value::<Vec<String>>()?),
-                Fileds::IsLoop => is_loop = map.next_value::<bool>()?,
-                Fileds::Index => index = map.next_value::<Attribute>()?,
-            }
-        }
-        let Some(frames) = frames else {return Err(Error::missing_field("Frames"));};
-        let Some(name) = name else {return Err(Error::missing_field("Name"));};
-        let mut handles = Vec::with_capacity(frames.len());
-        for frame in frames {
-            handles.push(self.0.get_handle::<_, Image>(&frame));
-            self.1.push(frame.into());
-        }
-        Ok(IndexNode {
-            id: None,
-            frames: handles,
-            name,
-            is_loop,
-            index,
-        })
-    }
+    // landing on a marked frame after a sync jump does not fire it
+    let sprint = IndexNode::new_with_index(
+        "sprint",
+        &vec![Handle::default(); 8],
+        true,
+        Attribute::new_index("Sprint"),
+    )
+    .with_sync_group("feet")
+    .with_marker(5, "step");
+    state.set_attribute(Attribute::Frames, 0usize);
+    sprint.run(&mut state).unwrap();
+    assert_eq!(state.index(&Attribute::new_index("Sprint")), 5);
+    assert_eq!(state.markers().count(), 0);
+
+    // a group that did not run for a whole update is forgotten
+    state.clear_changed();
+    state.clear_changed();
+    let jog = IndexNode::new_with_index(
+        "jog",
+        &vec![Handle::default(); 8],
+        true,
+        Attribute::new_index("Jog"),
+    )
+    .with_sync_group("feet");
+    jog.run(&mut state).unwrap();
+    assert_eq!(state.index(&Attribute::new_index("Jog")), 0);
+}
+
+#[test]
+fn shared_index_restarts_clip() {
+    let loops = Attribute::new_attribute("Loops");
+    let finished = Attribute::new_attribute("Finished");
+    let attack = IndexNode::new("attack", &vec![Handle::default(); 4], true)
+        .with_loops(loops.clone())
+        .with_finished(finished.clone());
+    let stand = IndexNode::new("stand", &vec![Handle::default(); 4], false)
+        .with_loops(loops.clone())
+        .with_finished(finished.clone());
+    let mut state = AnimationState::default();
+    state.set_attribute(Attribute::Frames, 5usize);
+    attack.run(&mut state).unwrap();
+    assert_eq!(*state.attribute::<usize>(&loops), 1);
+    assert!(*state.attribute::<bool>(&finished));
+
+    // switching to a clip with the same index counts from the start of that clip
+    state.set_attribute(Attribute::Frames, 0usize);
+    stand.run(&mut state).unwrap();
+    assert_eq!(*state.attribute::<usize>(&loops), 0);
+    assert!(!*state.attribute::<bool>(&finished));
+    state.set_attribute(Attribute::Frames, 1usize);
+    stand.run(&mut state).unwrap();
+    assert_eq!(*state.attribute::<usize>(&loops), 0);
+    state.set_attribute(Attribute::Frames, 0usize);
+    attack.run(&mut state).unwrap();
+    assert_eq!(*state.attribute::<usize>(&loops), 0);
 }
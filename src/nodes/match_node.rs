@@ -44,6 +44,8 @@ pub struct MatchNode<T: MatchType> {
     pairs: HashMap<T, NodeId<'static>>,
     check: Attribute,
     default: NodeId<'static>,
+    /// when set the matched node is called and this is run when it returns
+    then: Option<NodeId<'static>>,
 }
 
 impl<T: MatchType> MatchNode<T> {
@@ -64,8 +66,15 @@ impl<T: MatchType> MatchNode<T> {
             pairs,
             check,
             default,
+            then: None,
         }
     }
+
+    /// Calls the matched node rather then going to it, `then` is run when it returns
+    pub fn with_then(mut self, then: NodeId<'static>) -> MatchNode<T> {
+        self.then = Some(then);
+        self
+    }
 }
 
 impl<T> AnimationNodeTrait for MatchNode<T>
@@ -78,10 +87,15 @@ where
             Err(e) => return Err(RunError::Custom(format!("Match: {}: {:?}", e, self.check))),
         };
 
-        Ok(if let Some(next) = self.pairs.get(&val) {
-            NodeResult::Next(next.clone())
+        let next = if let Some(next) = self.pairs.get(val) {
+            next.clone()
+        } else {
+            self.default.clone()
+        };
+        Ok(if let Some(then) = &self.then {
+            NodeResult::Call(next, then.to_static())
         } else {
-            NodeResult::Next(self.default.clone())
+            NodeResult::Next(next)
         })
     }
 
@@ -101,6 +115,15 @@ where
         self.id = Some(id.to_static());
     }
 
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        let mut next: Vec<_> = self.pairs.values().map(|id| id.to_static()).collect();
+        next.push(self.default.to_static());
+        if let Some(then) = &self.then {
+            next.push(then.to_static());
+        }
+        next
+    }
+
     #[cfg(feature = "dot")]
     fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
         this.dot(out);
@@ -109,7 +132,20 @@ where
             this.dot(out);
             out.push_str(" -> ");
             next.dot(out);
-            out.push_str(&format!("[label=\"{:?}\"];\n", index));
+            if self.then.is_some() {
+                out.push_str(&format!(
+                    "[label=\"{:?}\", style=bold, color=blue];\n",
+                    index
+                ));
+            } else {
+                out.push_str(&format!("[label=\"{:?}\"];\n", index));
+            }
+        }
+        if let Some(then) = &self.then {
+            this.dot(out);
+            out.push_str(" -> ");
+            then.dot(out);
+            out.push_str(" [label=\"then\", style=dashed];\n");
         }
     }
 }
@@ -208,6 +244,13 @@ impl<T: MatchType + serde::de::DeserializeOwned + TypePath> LoadNode for MatchNo
             .or_else(|e| Err(LoadError::Ron(e).add_offset(chars.file_position())))?;
         let default: NodeId = ron::from_str(default)
             .or_else(|e| Err(LoadError::Ron(e).add_offset(chars.file_position())))?;
+        let then = match map.get("then") {
+            Some(then) => Some(
+                ron::from_str::<NodeId>(then)
+                    .map_err(|e| LoadError::Ron(e).add_offset(chars.file_position()))?,
+            ),
+            None => None,
+        };
         let name = name[1..name.len() - 1].to_string();
         let pairs: HashMap<T, NodeId> = pairs.into_iter().collect();
         Ok(AnimationNode::new(MatchNode {
@@ -216,6 +259,7 @@ impl<T: MatchType + serde::de::DeserializeOwned + TypePath> LoadNode for MatchNo
             pairs,
             default,
             check,
+            then,
         }))
     }
 }
@@ -1,27 +1,50 @@
+pub use call_node::CallNode;
+pub use direction_node::{DirectionNode, DirectionSector};
 pub use fps_node::FPSNode;
+pub use frame_data::{Clip, ClipAttributes, ClipFrame, ClipNode, LoopMode};
+pub use index_atlas_node::IndexAtlasNode;
 pub use index_node::IndexNode;
 pub use match_node::MatchNode;
+pub use random_node::RandomNode;
 pub use reference_node::ReferenceNode;
+pub use return_node::ReturnNode;
 pub use scale_node::ScaleNode;
 pub use script_node::ScriptNode;
+pub use threshold_node::ThresholdNode;
+pub use variable_atlas_node::VariableAtlasNode;
 pub use variable_node::VariableNode;
 
+pub mod call_node;
+pub mod direction_node;
 pub mod fps_node;
+pub mod frame_data;
+pub mod index_atlas_node;
 pub mod index_node;
 pub mod match_node;
+pub mod random_node;
 pub mod reference_node;
+pub mod return_node;
 pub mod scale_node;
 pub mod script_node;
+pub mod threshold_node;
+pub mod variable_atlas_node;
 pub mod variable_node;
 
 pub(crate) mod type_registration {
     use super::*;
     use bevy::prelude::App;
     pub(crate) fn registor_nodes(app: &mut App) {
-        app.register_type::<FPSNode>()
+        app.register_type::<CallNode>()
+            .register_type::<DirectionNode>()
+            .register_type::<FPSNode>()
             .register_type::<IndexNode>()
+            .register_type::<IndexAtlasNode>()
+            .register_type::<RandomNode>()
+            .register_type::<ReturnNode>()
             .register_type::<ScriptNode>()
             .register_type::<ScaleNode>()
-            .register_type::<VariableNode>();
+            .register_type::<ThresholdNode>()
+            .register_type::<VariableNode>()
+            .register_type::<VariableAtlasNode>();
     }
 }
@@ -0,0 +1,204 @@
+use crate::prelude::*;
+use crate::serde::ReflectLoadNode;
+use bevy::reflect::Reflect;
+use bevy::reflect::ReflectDeserialize;
+use bevy::reflect::ReflectSerialize;
+use std::sync::OnceLock;
+
+/// Goes to one of `branches` picked at random, each branch is picked in proportion to its weight
+/// the random numbers come from [`AnimationState::random_f32`] so each entity has its own seeded sequence
+/// the picked branch is kept while the node runs every update and a new one is picked when the node is entered again
+/// if `choice` is set the index of the picked branch is stored in it instead and that branch is used until the attribute is cleared,
+/// give it a [`Lifetime`] to pick again after a while
+/// ```ron
+/// RandomNode(
+///     name: "Idle Fidget",
+///     branches: [(Id(1), 0.7), (Id(2), 0.2), (Id(3), 0.1)],
+///     choice: Some(Custom("Fidget")),
+/// )
+/// ```
+#[derive(serde::Serialize, serde::Deserialize, Reflect)]
+#[reflect(Serialize, Deserialize, LoadNode)]
+pub struct RandomNode {
+    #[serde(default)]
+    id: Option<NodeId<'static>>,
+    name: String,
+    branches: Vec<(NodeId<'static>, f32)>,
+    #[serde(default)]
+    choice: Option<Attribute>,
+    /// where the pick is kept when there is no `choice`
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pick: OnceLock<Attribute>,
+}
+
+impl crate::serde::LoadNode for RandomNode {
+    fn load<'b>(
+        s: &str,
+        _load_context: &mut bevy::asset::LoadContext<'b>,
+        _dependencies: &mut Vec<bevy::asset::AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let node = ron::from_str::<RandomNode>(s)?;
+        Ok(AnimationNode::new(node))
+    }
+}
+
+impl RandomNode {
+    pub fn new(name: &str, branches: Vec<(NodeId<'static>, f32)>) -> RandomNode {
+        RandomNode {
+            id: None,
+            name: name.to_string(),
+            branches,
+            choice: None,
+            pick: OnceLock::new(),
+        }
+    }
+
+    /// Keeps the picked branch in `choice` and uses it until the attribute is cleared
+    pub fn with_choice(mut self, choice: Attribute) -> RandomNode {
+        self.choice = Some(choice);
+        self
+    }
+
+    fn pick(&self, state: &mut AnimationState) -> Result<usize, RunError> {
+        let total = self
+            .branches
+            .iter()
+            .map(|(_, weight)| weight.max(0.0))
+            .sum::<f32>();
+        if total <= 0.0 {
+            return Err(RunError::Custom(format!(
+                "Random: {} has no branch with a weight above 0",
+                self.name
+            )));
+        }
+        let mut roll = state.random_f32() * total;
+        for (index, (_, weight)) in self.branches.iter().enumerate() {
+            let weight = weight.max(0.0);
+            if roll < weight {
+                return Ok(index);
+            }
+            roll -= weight;
+        }
+        // only reached when rounding leaves roll at the top of the range
+        Ok(self
+            .branches
+            .iter()
+            .rposition(|(_, weight)| *weight > 0.0)
+            .unwrap_or_default())
+    }
+}
+
+impl AnimationNodeTrait for RandomNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, state: &mut AnimationState) -> Result<NodeResult, RunError> {
+        let hold = match &self.choice {
+            Some(choice) => choice,
+            None => self.pick.get_or_init(|| self.id().private_index()),
+        };
+        let held = state
+            .get_attribute::<usize>(hold)
+            .ok()
+            .copied()
+            .filter(|index| *index < self.branches.len());
+        let index = match held {
+            Some(index) => index,
+            None => self.pick(state)?,
+        };
+        if self.choice.is_none() {
+            // set every run so it is only cleared after an update the node did not run
+            state.set_attribute_for(hold.clone(), index, Lifetime::FRAME);
+        } else if held.is_none() {
+            state.set_attribute(hold.clone(), index);
+        }
+        Ok(NodeResult::Next(self.branches[index].0.to_static()))
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+        self.pick = OnceLock::new();
+    }
+
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        self.branches.iter().map(|(id, _)| id.to_static()).collect()
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        match &self.choice {
+            Some(choice) => out.push_str(&format!(" [label=\"{}\\n{}\"];\n", self.name, choice)),
+            None => out.push_str(&format!(" [label=\"{}\"];\n", self.name)),
+        }
+        for (next, weight) in self.branches.iter() {
+            this.dot(out);
+            out.push_str(" -> ");
+            next.dot(out);
+            out.push_str(&format!(" [label=\"{}\", style=dotted];\n", weight));
+        }
+    }
+}
+
+#[test]
+fn random_node_weights() {
+    let node = RandomNode::new(
+        "fidget",
+        vec![
+            (NodeId::U64(1), 0.7),
+            (NodeId::U64(2), 0.2),
+            (NodeId::U64(3), 0.1),
+            (NodeId::U64(4), 0.0),
+        ],
+    );
+    let end_update = |state: &mut AnimationState| {
+        state.clear_unchanged_temp();
+        state.clear_changed();
+    };
+    let run = |seed: u64| {
+        let mut state = AnimationState::default();
+        state.seed_random(seed);
+        let mut counts = [0; 5];
+        for _ in 0..1000 {
+            let Ok(NodeResult::Next(NodeId::U64(next))) = node.run(&mut state) else {panic!("RandomNode did not go to a branch")};
+            counts[next as usize] += 1;
+            // the node is entered again after an update it did not run
+            end_update(&mut state);
+            end_update(&mut state);
+        }
+        counts
+    };
+    let counts = run(7);
+    assert_eq!(counts, run(7));
+    assert_ne!(counts, run(8));
+    assert_eq!(counts[4], 0);
+    assert!(counts[1] > counts[2] && counts[2] > counts[3] && counts[3] > 0);
+
+    let mut state = AnimationState::default();
+    let Ok(NodeResult::Next(first)) = node.run(&mut state) else {panic!("RandomNode did not go to a branch")};
+    for _ in 0..20 {
+        end_update(&mut state);
+        let Ok(NodeResult::Next(next)) = node.run(&mut state) else {panic!("RandomNode did not go to a branch")};
+        assert_eq!(next, first);
+    }
+
+    let choice = Attribute::new_attribute("Fidget");
+    let node = node.with_choice(choice.clone());
+    let mut state = AnimationState::default();
+    let Ok(NodeResult::Next(first)) = node.run(&mut state) else {panic!("RandomNode did not go to a branch")};
+    for _ in 0..10 {
+        let Ok(NodeResult::Next(next)) = node.run(&mut state) else {panic!("RandomNode did not go to a branch")};
+        assert_eq!(next, first);
+    }
+    assert!(state.get_attribute::<usize>(&choice).is_ok());
+}
@@ -38,6 +38,14 @@ impl AnimationNodeTrait for ReferenceNode {
         warn!("Can't Set Id of a ReferenceNode");
     }
 
+    /// only the first node is run but every node in the file is part of the graph
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        self.0
+            .iter()
+            .map(|node| handle_to_node(node.id()))
+            .collect()
+    }
+
     #[cfg(feature = "dot")]
     fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
         this.dot(out);
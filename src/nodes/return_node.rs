@@ -0,0 +1,65 @@
+use crate::prelude::*;
+use crate::serde::ReflectLoadNode;
+use bevy::reflect::Reflect;
+use bevy::reflect::ReflectDeserialize;
+use bevy::reflect::ReflectSerialize;
+
+/// Ends a subgraph that was run by a [`CallNode`] or [`NodeResult::Call`] and runs the node it was told to run after
+#[derive(serde::Serialize, serde::Deserialize, Reflect)]
+#[reflect(Serialize, Deserialize, LoadNode)]
+pub struct ReturnNode {
+    #[serde(default)]
+    id: Option<NodeId<'static>>,
+    name: String,
+}
+
+impl crate::serde::LoadNode for ReturnNode {
+    fn load<'b>(
+        s: &str,
+        _load_context: &mut bevy::asset::LoadContext<'b>,
+        _dependencies: &mut Vec<bevy::asset::AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let node = ron::from_str::<ReturnNode>(s)?;
+        Ok(AnimationNode::new(node))
+    }
+}
+
+impl ReturnNode {
+    pub fn new(name: &str) -> ReturnNode {
+        ReturnNode {
+            id: None,
+            name: name.to_string(),
+        }
+    }
+}
+
+impl AnimationNodeTrait for ReturnNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, _: &mut AnimationState) -> Result<NodeResult, RunError> {
+        Ok(NodeResult::Return)
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        out.push_str(&format!(
+            " [label=\"{}\", shape=doublecircle];\n",
+            self.name
+        ));
+    }
+}
@@ -55,6 +55,10 @@ impl AnimationNodeTrait for ScaleNode {
         self.id = Some(id.to_static())
     }
 
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        vec![self.next.to_static()]
+    }
+
     fn id(&self) -> NodeId {
         if let Some(id) = &self.id {
             id.to_static()
@@ -89,6 +89,12 @@ impl AnimationNodeTrait for ScriptNode {
                 Token::Return(id) => {
                     return Ok(NodeResult::Next(id.to_static()));
                 }
+                Token::Call(id, then) => {
+                    return Ok(NodeResult::Call(id.to_static(), then.to_static()));
+                }
+                Token::ReturnCaller => {
+                    return Ok(NodeResult::Return);
+                }
                 _ => {
                     bevy::log::info!("pointer {}", index);
                     bevy::log::info!("statck = {:?}", self.tokens);
@@ -144,6 +150,22 @@ impl AnimationNodeTrait for ScriptNode {
         self.tags.push(Tag::ID(new.to_static()))
     }
 
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        let mut next: Vec<_> = self
+            .tokens
+            .iter()
+            .flat_map(|token| match token {
+                Token::Return(id) => vec![id.to_static()],
+                Token::Call(id, then) => vec![id.to_static(), then.to_static()],
+                _ => Vec::new(),
+            })
+            .collect();
+        if let Some(fallback) = &self.fallback {
+            next.push(fallback.to_static());
+        }
+        next
+    }
+
     fn serialize(
         &self,
         data: &mut String,
@@ -185,6 +207,16 @@ impl AnimationNodeTrait for ScriptNode {
                 id.dot(out);
                 out.push_str(&format!(";\n"));
             }
+            if let Token::Call(id, then) = token {
+                this.dot(out);
+                out.push_str(" -> ");
+                id.dot(out);
+                out.push_str(" [label=\"call\", style=bold, color=blue];\n");
+                this.dot(out);
+                out.push_str(" -> ");
+                then.dot(out);
+                out.push_str(" [label=\"then\", style=dashed];\n");
+            }
         }
     }
 }
@@ -192,7 +224,9 @@ impl AnimationNodeTrait for ScriptNode {
 #[derive(Debug, PartialEq, Eq)]
 enum Token {
     Int(usize),
+    /// thousandths
     Float(usize),
+    Bool(bool),
     String(String),
     Raw(Vec<u8>),
     Plus,
@@ -214,6 +248,8 @@ enum Token {
     If,
     Else,
     Return(NodeId<'static>),
+    ReturnCaller,
+    Call(NodeId<'static>, NodeId<'static>),
     Ron(String),
     Unknown(String),
 }
@@ -222,7 +258,11 @@ impl ToString for Token {
     fn to_string(&self) -> String {
         match self {
             Token::Return(id) => format!("return {}", id),
+            Token::ReturnCaller => "return caller".to_string(),
+            Token::Call(id, then) => format!("call {} then {}", id, then),
             Token::Int(i) => format!("{}", i),
+            Token::Float(f) => format!("{}", *f as f32 / 1000.),
+            Token::Bool(b) => format!("{}", b),
             Token::Equals => "==".to_string(),
             Token::NotEquals => "!=".to_string(),
             Token::LessThen => "<".to_string(),
@@ -510,9 +550,25 @@ impl ScriptNode {
                 "else" => Token::Else,
                 "set" => Token::Set,
                 "none" => Token::None,
-                "return" => Token::Return(
-                    NodeId::from_str(words.next().expect("NodeId to follow return")).unwrap(),
-                ),
+                "true" => Token::Bool(true),
+                "false" => Token::Bool(false),
+                "return" => {
+                    let next = words.next().expect("NodeId or caller to follow return");
+                    if next == "caller" {
+                        Token::ReturnCaller
+                    } else {
+                        Token::Return(NodeId::from_str(next).unwrap())
+                    }
+                }
+                "call" => {
+                    let call = words.next().expect("NodeId to follow call");
+                    let call = NodeId::from_str(call).unwrap();
+                    if words.next() != Some("then") {
+                        panic!("call {} must be followed by then", call);
+                    }
+                    let then = words.next().expect("NodeId to follow then");
+                    Token::Call(call, NodeId::from_str(then).unwrap())
+                }
                 _ => Token::Unknown(word.to_string()),
             };
             tokens.push(token);
@@ -558,6 +614,27 @@ fn if_condishion(state: &AnimationState, lhs: &Token, op: &Token, rhs: &Token) -
             Token::NotEquals => state.get_attribute::<usize>(id).is_ok(),
             _ => panic!("unsupported operator for index"),
         },
+        (Token::Attribute(id), Token::Bool(val)) => {
+            let Ok(current) = state.get_attribute::<bool>(id) else {return false;};
+            match op {
+                Token::Equals => current == val,
+                Token::NotEquals => current != val,
+                _ => panic!("unsupported operator for bool"),
+            }
+        }
+        (Token::Attribute(id), Token::Float(thousandths)) => {
+            let Ok(current) = state.get_attribute::<f32>(id) else {return false;};
+            let val = *thousandths as f32 / 1000.;
+            match op {
+                Token::Equals => *current == val,
+                Token::NotEquals => *current != val,
+                Token::LestThenEq => *current <= val,
+                Token::GratterThenEq => *current >= val,
+                Token::LessThen => *current < val,
+                Token::GratterThen => *current > val,
+                _ => panic!("unsupported operator for float"),
+            }
+        }
         (Token::Attribute(_), _) => {
             panic!("unsupported operator for index")
         }
@@ -574,3 +651,51 @@ impl LoadNode for ScriptNode {
         Ok(crate::AnimationNode::new(ScriptNode::new(s)))
     }
 }
+
+#[test]
+fn call_and_return() {
+    let mut state = AnimationState::default();
+    let call = ScriptNode::new("#name call call Id(1) then Id(2)");
+    assert!(matches!(
+        call.run(&mut state),
+        Ok(NodeResult::Call(NodeId::U64(1), NodeId::U64(2)))
+    ));
+    assert_eq!(call.successors(), vec![NodeId::U64(1), NodeId::U64(2)]);
+    let back = ScriptNode::new("#name back return caller");
+    assert!(matches!(back.run(&mut state), Ok(NodeResult::Return)));
+}
+
+#[test]
+fn clip_finished_and_progress() {
+    use super::ClipNode;
+    let walk = super::IndexNode::new("walk", &vec![bevy::prelude::Handle::default(); 4], false)
+        .with_finished(Attribute::new_attribute("WalkDone"))
+        .with_progress(Attribute::new_attribute("WalkProgress"))
+        .with_loops(Attribute::new_attribute("WalkLoops"));
+    let done = ScriptNode::new("#name done if Attribute(\"WalkDone\") == true return Id(1)");
+    let half = ScriptNode::new("#name half if Attribute(\"WalkProgress\") >= 0.5 return Id(2)");
+    let mut state = AnimationState::default();
+    state.set_attribute(Attribute::Frames, 2usize);
+    walk.run(&mut state).unwrap();
+    assert!(!*state.attribute::<bool>(&Attribute::new_attribute("WalkDone")));
+    assert!(done.run(&mut state).is_err());
+    assert!(matches!(
+        half.run(&mut state),
+        Ok(NodeResult::Next(NodeId::U64(2)))
+    ));
+    walk.run(&mut state).unwrap();
+    assert!(matches!(
+        done.run(&mut state),
+        Ok(NodeResult::Next(NodeId::U64(1)))
+    ));
+    assert_eq!(
+        *state.attribute::<usize>(&Attribute::new_attribute("WalkLoops")),
+        1
+    );
+    walk.run(&mut state).unwrap();
+    assert!(!*state.attribute::<bool>(&Attribute::new_attribute("WalkDone")));
+    assert_eq!(
+        *state.attribute::<f32>(&Attribute::new_attribute("WalkProgress")),
+        1.0
+    );
+}
@@ -0,0 +1,245 @@
+use crate::prelude::*;
+use crate::serde::ReflectLoadNode;
+use bevy::reflect::Reflect;
+use bevy::reflect::ReflectDeserialize;
+use bevy::reflect::ReflectSerialize;
+use std::sync::OnceLock;
+
+/// Goes to the node of the first threshold `check` is below, or `above` if it is not below any
+/// `check` can be an `f32` or a `usize`
+/// with `hysteresis` the value has to go that far past a threshold before the node changes
+/// so a value sitting on a threshold does not flicker between two clips,
+/// the last picked band is kept in an index attribute made from the node id
+/// ```ron
+/// ThresholdNode(
+///     name: "Gait",
+///     check: Custom("Speed"),
+///     thresholds: [(0.1, Id(3)), (3.0, Id(4))],
+///     above: Id(5),
+///     hysteresis: 0.2,
+/// )
+/// ```
+#[derive(serde::Serialize, serde::Deserialize, Reflect)]
+#[reflect(Serialize, Deserialize, LoadNode)]
+pub struct ThresholdNode {
+    #[serde(default)]
+    id: Option<NodeId<'static>>,
+    name: String,
+    check: Attribute,
+    #[serde(deserialize_with = "sorted_thresholds")]
+    thresholds: Vec<(f32, NodeId<'static>)>,
+    above: NodeId<'static>,
+    #[serde(default)]
+    hysteresis: f32,
+    /// where the last picked band is kept
+    #[serde(skip)]
+    #[reflect(ignore)]
+    band: OnceLock<Attribute>,
+}
+
+/// the thresholds in the order [`ThresholdNode::band`] checks them, however they were written
+fn sorted_thresholds<'de, D>(deserializer: D) -> Result<Vec<(f32, NodeId<'static>)>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut thresholds: Vec<(f32, NodeId<'static>)> =
+        serde::Deserialize::deserialize(deserializer)?;
+    thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(thresholds)
+}
+
+impl crate::serde::LoadNode for ThresholdNode {
+    fn load<'b>(
+        s: &str,
+        _load_context: &mut bevy::asset::LoadContext<'b>,
+        _dependencies: &mut Vec<bevy::asset::AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let node = ron::from_str::<ThresholdNode>(s)?;
+        Ok(AnimationNode::new(node))
+    }
+}
+
+impl ThresholdNode {
+    /// the thresholds do not need to be in order
+    pub fn new(
+        name: &str,
+        check: Attribute,
+        mut thresholds: Vec<(f32, NodeId<'static>)>,
+        above: NodeId<'static>,
+    ) -> ThresholdNode {
+        thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ThresholdNode {
+            id: None,
+            name: name.to_string(),
+            check,
+            thresholds,
+            above,
+            hysteresis: 0.,
+            band: OnceLock::new(),
+        }
+    }
+
+    /// how far past a threshold the value must go before the node changes
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> ThresholdNode {
+        self.hysteresis = hysteresis.abs();
+        self
+    }
+
+    fn band_attribute(&self) -> &Attribute {
+        self.band.get_or_init(|| self.id().private_index())
+    }
+
+    fn value(&self, state: &AnimationState) -> Result<f32, RunError> {
+        if let Ok(value) = state.get_attribute::<f32>(&self.check) {
+            return Ok(*value);
+        }
+        match state.get_attribute::<usize>(&self.check) {
+            Ok(value) => Ok(*value as f32),
+            Err(e) => Err(RunError::Custom(format!(
+                "Threshold: {}: {} must be an f32 or a usize",
+                e, self.check
+            ))),
+        }
+    }
+
+    /// the band `value` is in, thresholds below `last` are moved down and the rest up by the hysteresis
+    fn band(&self, value: f32, last: Option<usize>) -> usize {
+        self.thresholds
+            .iter()
+            .enumerate()
+            .position(|(index, (threshold, _))| match last {
+                Some(last) if index < last => value < threshold - self.hysteresis,
+                Some(_) => value < threshold + self.hysteresis,
+                None => value < *threshold,
+            })
+            .unwrap_or(self.thresholds.len())
+    }
+}
+
+impl AnimationNodeTrait for ThresholdNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, state: &mut AnimationState) -> Result<NodeResult, RunError> {
+        let value = self.value(state)?;
+        let next = if self.hysteresis > 0. {
+            let attribute = self.band_attribute();
+            let last = state
+                .get_attribute::<usize>(attribute)
+                .ok()
+                .copied()
+                .filter(|band| *band <= self.thresholds.len());
+            let band = self.band(value, last);
+            if last != Some(band) {
+                state.set_attribute(attribute.clone(), band);
+            }
+            band
+        } else {
+            self.band(value, None)
+        };
+        Ok(NodeResult::Next(match self.thresholds.get(next) {
+            Some((_, next)) => next.to_static(),
+            None => self.above.to_static(),
+        }))
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+        self.band = OnceLock::new();
+    }
+
+    fn successors(&self) -> Vec<NodeId<'static>> {
+        let mut next: Vec<_> = self
+            .thresholds
+            .iter()
+            .map(|(_, id)| id.to_static())
+            .collect();
+        next.push(self.above.to_static());
+        next
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, _: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        if self.hysteresis > 0. {
+            out.push_str(&format!(
+                " [label=\"{}\\n{} ±{}\"];\n",
+                self.name, self.check, self.hysteresis
+            ));
+        } else {
+            out.push_str(&format!(" [label=\"{}\\n{}\"];\n", self.name, self.check));
+        }
+        for (threshold, next) in self.thresholds.iter() {
+            this.dot(out);
+            out.push_str(" -> ");
+            next.dot(out);
+            out.push_str(&format!(" [label=\"< {}\"];\n", threshold));
+        }
+        this.dot(out);
+        out.push_str(" -> ");
+        self.above.dot(out);
+        match self.thresholds.last() {
+            Some((threshold, _)) => out.push_str(&format!(" [label=\">= {}\"];\n", threshold)),
+            None => out.push_str(" [label=\"always\"];\n"),
+        }
+    }
+}
+
+#[test]
+fn threshold_hysteresis() {
+    let speed = Attribute::new_attribute("Speed");
+    let node = ThresholdNode::new(
+        "gait",
+        speed.clone(),
+        vec![(3.0, NodeId::U64(2)), (0.1, NodeId::U64(1))],
+        NodeId::U64(3),
+    );
+    let pick = |node: &ThresholdNode, state: &mut AnimationState, value: f32| {
+        state.set_attribute(speed.clone(), value);
+        let Ok(NodeResult::Next(NodeId::U64(next))) = node.run(state) else {panic!("ThresholdNode did not pick a node")};
+        next
+    };
+    let mut state = AnimationState::default();
+    assert_eq!(pick(&node, &mut state, 0.05), 1);
+    assert_eq!(pick(&node, &mut state, 2.0), 2);
+    assert_eq!(pick(&node, &mut state, 3.0), 3);
+    state.set_attribute(speed.clone(), 2usize);
+    assert!(matches!(
+        node.run(&mut state),
+        Ok(NodeResult::Next(NodeId::U64(2)))
+    ));
+
+    let node = node.with_hysteresis(0.5);
+    let mut state = AnimationState::default();
+    assert_eq!(pick(&node, &mut state, 2.9), 2);
+    assert_eq!(pick(&node, &mut state, 3.2), 2);
+    assert_eq!(pick(&node, &mut state, 3.6), 3);
+    assert_eq!(pick(&node, &mut state, 2.8), 3);
+    assert_eq!(pick(&node, &mut state, 2.4), 2);
+
+    let loaded = ron::from_str::<ThresholdNode>(
+        r#"ThresholdNode(
+            name: "Gait",
+            check: Custom("Speed"),
+            thresholds: [(3.0, Id(4)), (0.1, Id(3))],
+            above: Id(5),
+            hysteresis: 0.2,
+        )"#,
+    )
+    .unwrap();
+    assert_eq!(loaded.successors().len(), 3);
+    state.set_attribute(Attribute::new_attribute("Speed"), 1.0f32);
+    assert!(matches!(
+        loaded.run(&mut state),
+        Ok(NodeResult::Next(NodeId::U64(4)))
+    ));
+}
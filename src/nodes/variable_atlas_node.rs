@@ -0,0 +1,114 @@
+use crate::nodes::frame_data::{Clip, ClipFields, ClipNode, FrameData, LoopMode};
+use crate::prelude::*;
+use crate::serde::LoadNode;
+use crate::serde::ReflectLoadNode;
+use bevy::asset::AssetPath;
+use bevy::prelude::Handle;
+use bevy::reflect::Reflect;
+use bevy::sprite::TextureAtlas;
+
+/// Works like an [`VariableNode`] but picks frames out of a `TextureAtlas`
+/// each frame is the index of the sprite in the atlas and how long to show it for
+#[derive(Debug, Reflect)]
+#[reflect(LoadNode)]
+pub struct VariableAtlasNode {
+    id: Option<NodeId<'static>>,
+    name: String,
+    atlas: Handle<TextureAtlas>,
+    clip: Clip<(usize, f32)>,
+}
+
+impl VariableAtlasNode {
+    #[inline(always)]
+    pub fn new(
+        name: &str,
+        atlas: Handle<TextureAtlas>,
+        frames: &[(usize, f32)],
+        mode: impl Into<LoopMode>,
+    ) -> VariableAtlasNode {
+        VariableAtlasNode::new_with_index(name, atlas, frames, mode, Attribute::IndexId(0))
+    }
+
+    #[inline(always)]
+    pub fn new_with_index(
+        name: &str,
+        atlas: Handle<TextureAtlas>,
+        frames: &[(usize, f32)],
+        mode: impl Into<LoopMode>,
+        index: Attribute,
+    ) -> VariableAtlasNode {
+        VariableAtlasNode {
+            id: None,
+            name: name.to_string(),
+            atlas,
+            clip: Clip::new(frames.to_vec(), mode.into(), index),
+        }
+    }
+}
+
+impl ClipNode for VariableAtlasNode {
+    fn frame_data_mut(&mut self) -> &mut FrameData {
+        self.clip.frame_data_mut()
+    }
+}
+
+impl AnimationNodeTrait for VariableAtlasNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, state: &mut AnimationState) -> Result<NodeResult, RunError> {
+        let (frame, _) = *self.clip.run(self.id(), state);
+        Ok(NodeResult::DoneAtlas(self.atlas.clone(), frame))
+    }
+
+    fn id(&self) -> NodeId<'_> {
+        if let Some(id) = &self.id {
+            id.to_static()
+        } else {
+            NodeId::from_name(&self.name)
+        }
+    }
+
+    fn set_id(&mut self, id: NodeId<'_>) {
+        self.id = Some(id.to_static());
+    }
+
+    #[cfg(feature = "dot")]
+    fn dot(&self, this: NodeId<'_>, out: &mut String, asset_server: &bevy::prelude::AssetServer) {
+        this.dot(out);
+        out.push_str(&format!(" [label=\"{}\"];\n", self.name));
+        this.dot(out);
+        out.push_str(" -> ");
+        let h = handle_to_node(self.atlas.id());
+        h.dot(out);
+        out.push_str(&format!(" [label=\"{:?}\"];\n", self.clip.frames()));
+        if let Some(path) = asset_server.get_handle_path(&self.atlas) {
+            h.dot(out);
+            out.push_str(&format!(
+                " [label=\"{}\"];\n",
+                path.label().unwrap_or("atlas")
+            ));
+            h.dot(out);
+            out.push_str(" [color=green];\n");
+        }
+    }
+}
+
+impl LoadNode for VariableAtlasNode {
+    fn load<'b>(
+        s: &str,
+        load_context: &mut bevy::asset::LoadContext<'b>,
+        dependencies: &mut Vec<AssetPath<'static>>,
+    ) -> Result<AnimationNode, crate::error::LoadError> {
+        let mut fields = ClipFields::<(usize, f32)>::from_ron(s, "VariableAtlasNode", true)?;
+        let Some(atlas) = fields.atlas.take() else {unreachable!("ClipFields checks atlas nodes have an atlas")};
+        let atlas = atlas.load(&fields.name, load_context, dependencies);
+        Ok(AnimationNode::new(VariableAtlasNode {
+            id: None,
+            name: fields.name.clone(),
+            atlas,
+            clip: fields.into_clip(|frame| frame),
+        }))
+    }
+}
@@ -0,0 +1,110 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+/// Controls how fast the animation of an entity plays
+/// entities without this play at normal speed using the scaled [`Time`]
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+pub struct AnimationPlayback {
+    /// the delta is multiplied by this before it is given to the nodes
+    pub speed: f32,
+    /// when true the delta given to the nodes is 0
+    pub paused: bool,
+    /// where the delta comes from
+    pub time_source: TimeSource,
+}
+
+impl Default for AnimationPlayback {
+    fn default() -> Self {
+        AnimationPlayback {
+            speed: 1.0,
+            paused: false,
+            time_source: TimeSource::Scaled,
+        }
+    }
+}
+
+impl AnimationPlayback {
+    pub fn new(speed: f32) -> AnimationPlayback {
+        AnimationPlayback {
+            speed,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_time_source(mut self, time_source: TimeSource) -> AnimationPlayback {
+        self.time_source = time_source;
+        self
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// the delta this entity will use, given the delta from its time source
+    pub fn scale(&self, delta: f32) -> f32 {
+        if self.paused {
+            0.0
+        } else {
+            delta * self.speed
+        }
+    }
+}
+
+/// The clock an entity takes its delta from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum TimeSource {
+    /// [`Time::delta_seconds`], this is paused and scaled with `Time`
+    #[default]
+    Scaled,
+    /// [`Time::raw_delta_seconds`], this keeps running when `Time` is paused
+    Unscaled,
+    /// the clock added with [`AnimationClockPlugin`]
+    Custom,
+}
+
+/// A resource that can be used as the clock for [`TimeSource::Custom`]
+pub trait AnimationClock: Resource {
+    /// the time in seconds since the last update
+    fn delta_seconds(&self) -> f32;
+}
+
+/// Makes `C` the clock used by entities with [`TimeSource::Custom`]
+/// the resource `C` must be added to the app separately
+pub struct AnimationClockPlugin<C: AnimationClock>(PhantomData<C>);
+
+impl<C: AnimationClock> Default for AnimationClockPlugin<C> {
+    fn default() -> Self {
+        AnimationClockPlugin(PhantomData)
+    }
+}
+
+impl<C: AnimationClock> Plugin for AnimationClockPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CustomClockDelta>();
+        app.add_systems(Update, read_clock::<C>.before(crate::state::update_delta));
+    }
+}
+
+/// The delta of the custom clock this frame
+#[derive(Debug, Default, Resource)]
+pub(crate) struct CustomClockDelta(pub(crate) f32);
+
+fn read_clock<C: AnimationClock>(clock: Option<Res<C>>, mut delta: ResMut<CustomClockDelta>) {
+    delta.0 = clock.map(|clock| clock.delta_seconds()).unwrap_or_default();
+}
+
+#[test]
+fn scale_delta() {
+    let mut playback = AnimationPlayback::new(0.5);
+    assert_eq!(playback.scale(0.2), 0.1);
+    playback.pause();
+    assert_eq!(playback.scale(0.2), 0.0);
+    playback.resume();
+    assert_eq!(playback.scale(0.2), 0.1);
+}
@@ -1,16 +1,28 @@
-pub use super::attributes::Attribute;
+pub use super::animator::{AnimatorBundle, AnimatorPreset};
+pub use super::attributes::{core_attribute, Attribute, TypedAttribute};
+pub use super::binding::{AttributeBinding, BindingDirection};
 pub use super::error::BevySpriteAnimationError;
 pub use super::error::RunError;
+pub use super::events::AnimationEvent;
+pub use super::events::FrameChanged;
+pub use super::hitbox::{ActiveHitboxes, HitShape, Hitbox};
 pub use super::node_core::AnimationNodeTrait;
 pub use super::node_core::NodeResult;
 pub use super::node_id::NodeId;
 pub use super::nodes::*;
+pub use super::playback::{AnimationClock, AnimationClockPlugin, AnimationPlayback, TimeSource};
+pub use super::program::{AnimationProgram, AnimationPrograms};
+pub use super::root_motion::NoRootMotion;
 #[cfg(feature = "serialize")]
 pub use super::serde::ReflectLoadNode;
-pub use super::state::AnimationState;
+pub use super::simulate::{simulate, SimulatedFrame, Simulation, SimulationStep};
+pub use super::state::{AnimationState, AnimationStateDeserializer, ReflectAnimationStateObj};
 pub use super::system_set::AnimationSet;
+pub use super::target::{AnimationOutput, AnimationTarget, AnimationTargetPlugin};
+pub use super::timers::{AttributeTimer, Lifetime};
 pub use super::utils::get_node_hash;
 pub use super::AnimationNode;
+pub use super::AnimationSettings;
 pub use super::SpriteAnimationPlugin;
 pub use super::StartNode;
 pub(crate) use crate::node_id::handle_to_node;
@@ -0,0 +1,194 @@
+use bevy::asset::HandleId;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// A node graph flattened into an array so it can be run without looking up every node in `Assets<AnimationNode>`
+/// the node at index 0 is the start node and the successors of each node are stored as indexes into the array
+/// the loaded nodes are kept with the program so running it does not look them up in `Assets<AnimationNode>`
+#[derive(Default)]
+pub struct AnimationProgram {
+    nodes: Vec<HandleId>,
+    resolved: Vec<Option<Arc<dyn AnimationNodeTrait>>>,
+    successors: Vec<Vec<usize>>,
+    lookup: HashMap<HandleId, usize>,
+}
+
+impl std::fmt::Debug for AnimationProgram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnimationProgram")
+            .field("nodes", &self.nodes)
+            .field("successors", &self.successors)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AnimationProgram {
+    /// follows [`AnimationNodeTrait::successors`] from `start` to find every node that can be reached
+    /// nodes that are not loaded yet are kept in the program so it can be rebuilt when they are
+    pub fn compile(start: HandleId, nodes: &Assets<AnimationNode>) -> AnimationProgram {
+        let mut program = AnimationProgram::default();
+        program.insert(start);
+        let mut next = 0;
+        while next < program.nodes.len() {
+            let node = nodes.get(&Handle::weak(program.nodes[next]));
+            let successors = match node {
+                Some(node) => node
+                    .successors()
+                    .iter()
+                    .map(|id| program.insert(HandleId::from(id)))
+                    .collect(),
+                None => Vec::new(),
+            };
+            program.resolved.push(node.map(|node| node.0.clone()));
+            program.successors.push(successors);
+            next += 1;
+        }
+        program
+    }
+
+    fn insert(&mut self, id: HandleId) -> usize {
+        if let Some(index) = self.lookup.get(&id) {
+            return *index;
+        }
+        let index = self.nodes.len();
+        self.nodes.push(id);
+        self.lookup.insert(id, index);
+        index
+    }
+
+    /// the nodes in this program, the first one is the start node
+    pub fn nodes(&self) -> &[HandleId] {
+        &self.nodes
+    }
+
+    /// the indexes of the nodes that the node at `index` can go to
+    pub fn successors(&self, index: usize) -> &[usize] {
+        &self.successors[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, id: &HandleId) -> bool {
+        self.lookup.contains_key(id)
+    }
+
+    /// the index of `id` in this program, `from` is the index of the node that returned it
+    /// the successors of `from` are checked first so most steps do not need to hash `id`
+    pub fn find(&self, from: Option<usize>, id: HandleId) -> Option<usize> {
+        match from {
+            Some(from) => {
+                if let Some(index) = self.successors[from]
+                    .iter()
+                    .find(|index| self.nodes[**index] == id)
+                {
+                    return Some(*index);
+                }
+            }
+            None => {
+                if self.nodes.first() == Some(&id) {
+                    return Some(0);
+                }
+            }
+        }
+        self.lookup.get(&id).copied()
+    }
+
+    /// the index and node for `id`, `from` is the index of the node that returned it
+    /// `None` if `id` is not in this program or was not loaded when it was compiled
+    pub fn find_node(
+        &self,
+        from: Option<usize>,
+        id: HandleId,
+    ) -> Option<(usize, &dyn AnimationNodeTrait)> {
+        let index = self.find(from, id)?;
+        self.resolved[index].as_deref().map(|node| (index, node))
+    }
+}
+
+/// The compiled programs for every [`StartNode`] in use
+#[derive(Debug, Default, Resource)]
+pub struct AnimationPrograms {
+    programs: HashMap<HandleId, AnimationProgram>,
+}
+
+impl AnimationPrograms {
+    pub fn get(&self, start: &NodeId<'_>) -> Option<&AnimationProgram> {
+        self.programs.get(&HandleId::from(start))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&HandleId, &AnimationProgram)> {
+        self.programs.iter()
+    }
+
+    /// removes all programs, they will be compiled again for any [`StartNode`] that is changed or added
+    pub fn clear(&mut self) {
+        self.programs.clear();
+    }
+}
+
+/// Compiles a program for new start nodes and rebuilds programs when any of there nodes change
+pub(crate) fn compile_programs(
+    nodes: Res<Assets<AnimationNode>>,
+    mut events: EventReader<AssetEvent<AnimationNode>>,
+    mut programs: ResMut<AnimationPrograms>,
+    starts: Query<&StartNode, Changed<StartNode>>,
+) {
+    let mut changed = HashSet::new();
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle }
+            | AssetEvent::Modified { handle }
+            | AssetEvent::Removed { handle } => changed.insert(handle.id()),
+        };
+    }
+    let programs = programs.as_mut();
+    if !changed.is_empty() {
+        for (start, program) in programs.programs.iter_mut() {
+            if changed.iter().any(|id| program.contains(id)) {
+                trace!("Rebuilding program for {:?}", start);
+                *program = AnimationProgram::compile(*start, &nodes);
+            }
+        }
+    }
+    for start in starts.iter() {
+        let start = HandleId::from(&start.0);
+        programs
+            .programs
+            .entry(start)
+            .or_insert_with(|| AnimationProgram::compile(start, &nodes));
+    }
+}
+
+#[test]
+fn compile_follows_successors() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .add_asset::<AnimationNode>();
+    let mut nodes = app.world.resource_mut::<Assets<AnimationNode>>();
+    let clip = NodeId::from_name("clip");
+    nodes.set_untracked(
+        &clip,
+        AnimationNode::new(IndexNode::new("clip", &[Handle::default()], true)),
+    );
+    let start = NodeId::from_name("start");
+    nodes.set_untracked(
+        &start,
+        AnimationNode::new(FPSNode::new("start", 10, clip.to_static())),
+    );
+    let program = AnimationProgram::compile(HandleId::from(&start), &nodes);
+    assert_eq!(program.len(), 2);
+    assert_eq!(program.successors(0), &[1]);
+    assert_eq!(program.find(Some(0), HandleId::from(&clip)), Some(1));
+    assert_eq!(program.find(None, HandleId::from(&start)), Some(0));
+    let (index, node) = program.find_node(Some(0), HandleId::from(&clip)).unwrap();
+    assert_eq!((index, node.name()), (1, "clip"));
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Stops the root motion of an entities frames being applied to its `Transform`
+/// use this when something else like physics owns the transform, the motion can still be read with [`AnimationState::root_motion`]
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+#[reflect(Component)]
+pub struct NoRootMotion;
+
+/// Moves entities by the root motion of the frames they reached this frame
+/// the offset is in sprite-local space so it is mirrored by [`Attribute::FlipX`] and [`Attribute::FlipY`]
+pub(crate) fn apply_root_motion(
+    mut query: Query<(&AnimationState, &mut Transform), Without<NoRootMotion>>,
+) {
+    for (state, mut transform) in query.iter_mut() {
+        let (mut offset, mut rotation) = state.root_motion();
+        if offset == Vec2::ZERO && rotation == 0.0 {
+            continue;
+        }
+        let flip_x = state
+            .get_attribute::<bool>(&Attribute::FlipX)
+            .cloned()
+            .unwrap_or_default();
+        let flip_y = state
+            .get_attribute::<bool>(&Attribute::FlipY)
+            .cloned()
+            .unwrap_or_default();
+        if flip_x {
+            offset.x = -offset.x;
+            rotation = -rotation;
+        }
+        if flip_y {
+            offset.y = -offset.y;
+            rotation = -rotation;
+        }
+        let offset = transform.rotation * offset.extend(0.0);
+        transform.translation += offset;
+        transform.rotate_z(rotation);
+    }
+}
@@ -0,0 +1,206 @@
+use std::borrow::Cow;
+
+use bevy::asset::HandleId;
+use bevy::prelude::*;
+
+use crate::error::RunError;
+use crate::prelude::*;
+use crate::program::AnimationProgram;
+use crate::state::AnimationStateObj;
+
+/// Runs a node graph without an `App`, this is useful for testing `.nodetree` files
+/// each step does the same work the plugin does for an entity in one frame
+/// ```ignore
+/// let mut sim = Simulation::new(NodeId::from_name("Zombie1_SwitchNode"), &nodes, state);
+/// sim.set_attribute(Attribute::new_attribute("ZombieState"), ZState::Attacking);
+/// let frames = sim.advance(0.1, 5);
+/// assert_eq!(frames[4].image(), Some(&attack[4]));
+/// ```
+pub struct Simulation<'a> {
+    start: NodeId<'static>,
+    nodes: &'a Assets<AnimationNode>,
+    state: AnimationState,
+    settings: AnimationSettings,
+    program: AnimationProgram,
+    frame_started: bool,
+}
+
+/// What happened in one step of a [`Simulation`]
+#[derive(Debug)]
+pub struct SimulatedFrame {
+    /// the frame the nodes picked
+    pub output: Option<AnimationOutput>,
+    /// the nodes that were run in the order they were run
+    pub visited: Vec<NodeId<'static>>,
+    /// the markers that were reached as `(node, marker, frame)`
+    pub markers: Vec<(NodeId<'static>, Cow<'static, str>, usize)>,
+    /// set if the nodes did not get to a frame
+    pub error: Option<RunError>,
+}
+
+impl SimulatedFrame {
+    /// the image that was picked, `None` if the nodes did not pick an image
+    pub fn image(&self) -> Option<&Handle<Image>> {
+        match &self.output {
+            Some(AnimationOutput::Image(image)) => Some(image),
+            _ => None,
+        }
+    }
+
+    /// the atlas index that was picked, `None` if the nodes did not pick an atlas
+    pub fn atlas_index(&self) -> Option<usize> {
+        match &self.output {
+            Some(AnimationOutput::Atlas(_, index)) => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+/// A step given to [`simulate`]
+pub enum SimulationStep {
+    /// run the nodes with this delta
+    Delta(f32),
+    /// change the state before the next delta like a gameplay system would
+    Change(Box<dyn FnOnce(&mut AnimationState)>),
+}
+
+impl SimulationStep {
+    pub fn set<D: AnimationStateObj>(attribute: Attribute, val: D) -> SimulationStep {
+        SimulationStep::Change(Box::new(move |state| state.set_attribute(attribute, val)))
+    }
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(
+        start: impl Into<NodeId<'static>>,
+        nodes: &'a Assets<AnimationNode>,
+        state: AnimationState,
+    ) -> Simulation<'a> {
+        let start = start.into();
+        Simulation {
+            program: AnimationProgram::compile(HandleId::from(&start), nodes),
+            start,
+            nodes,
+            state,
+            settings: AnimationSettings::default(),
+            frame_started: false,
+        }
+    }
+
+    pub fn with_settings(mut self, settings: AnimationSettings) -> Simulation<'a> {
+        self.settings = settings;
+        self
+    }
+
+    pub fn state(&self) -> &AnimationState {
+        &self.state
+    }
+
+    /// changes to the state are seen by the nodes in the next step
+    pub fn state_mut(&mut self) -> &mut AnimationState {
+        self.start_frame();
+        &mut self.state
+    }
+
+    pub fn set_attribute<D: AnimationStateObj>(&mut self, key: Attribute, val: D) {
+        self.state_mut().set_attribute(key, val);
+    }
+
+    fn start_frame(&mut self) {
+        if !self.frame_started {
+            self.state.clear_changed();
+            self.frame_started = true;
+        }
+    }
+
+    /// runs the nodes once with `delta` as [`Attribute::Delta`]
+    pub fn step(&mut self, delta: f32) -> SimulatedFrame {
+        self.start_frame();
+        self.frame_started = false;
+        self.state.set_attribute(Attribute::Delta, delta);
+        self.state.advance_timers();
+        let result = crate::run_nodes(
+            &self.start,
+            &mut self.state,
+            self.nodes,
+            Some(&self.program),
+            &self.settings,
+        );
+        let frame = SimulatedFrame {
+            output: self.state.output().cloned(),
+            visited: self.state.visited().collect(),
+            markers: self.state.markers.clone(),
+            error: result.err(),
+        };
+        self.state.clear_unchanged_temp();
+        frame
+    }
+
+    /// runs `steps` steps of `delta`
+    pub fn advance(&mut self, delta: f32, steps: usize) -> Vec<SimulatedFrame> {
+        (0..steps).map(|_| self.step(delta)).collect()
+    }
+
+    /// consumes the simulation and gives back the state
+    pub fn into_state(self) -> AnimationState {
+        self.state
+    }
+}
+
+/// Runs `steps` on the graph starting at `start` and returns a [`SimulatedFrame`] for every [`SimulationStep::Delta`]
+pub fn simulate(
+    start: impl Into<NodeId<'static>>,
+    nodes: &Assets<AnimationNode>,
+    state: AnimationState,
+    steps: impl IntoIterator<Item = SimulationStep>,
+) -> (Vec<SimulatedFrame>, AnimationState) {
+    let mut sim = Simulation::new(start, nodes, state);
+    let mut frames = Vec::new();
+    for step in steps {
+        match step {
+            SimulationStep::Delta(delta) => frames.push(sim.step(delta)),
+            SimulationStep::Change(change) => change(sim.state_mut()),
+        }
+    }
+    (frames, sim.into_state())
+}
+
+#[test]
+fn simulate_clip() {
+    use bevy::reflect::TypeUuid;
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .add_asset::<AnimationNode>();
+    let mut nodes = app.world.resource_mut::<Assets<AnimationNode>>();
+    let images = (0..4)
+        .map(|i| Handle::weak(HandleId::new(Image::TYPE_UUID, i)))
+        .collect::<Vec<Handle<Image>>>();
+    let clip = NodeId::from_name("clip");
+    nodes.set_untracked(
+        &clip,
+        AnimationNode::new(IndexNode::new("clip", &images, false)),
+    );
+    let start = NodeId::from_name("start");
+    nodes.set_untracked(
+        &start,
+        AnimationNode::new(FPSNode::new("start", 10, clip.to_static())),
+    );
+    let (frames, state) = simulate(
+        start,
+        &nodes,
+        AnimationState::default(),
+        [
+            SimulationStep::Delta(0.25),
+            SimulationStep::set(Attribute::new_attribute("Hit"), true),
+            SimulationStep::Delta(0.1),
+            SimulationStep::Delta(1.0),
+        ],
+    );
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].image(), Some(&images[2]));
+    assert_eq!(frames[0].visited, vec![NodeId::from_name("start"), clip]);
+    assert_eq!(frames[1].image(), Some(&images[3]));
+    assert_eq!(frames[2].image(), Some(&images[3]));
+    assert!(frames.iter().all(|frame| frame.error.is_none()));
+    assert!(*state.attribute::<bool>(&Attribute::new_attribute("Hit")));
+}
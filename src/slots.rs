@@ -0,0 +1,315 @@
+use std::any::Any;
+
+use crate::attributes::Attribute;
+use crate::state::AnimationStateObj;
+use crate::timers::Lifetime;
+
+/// A value stored in an [`AnimationState`](crate::state::AnimationState)
+/// the common small types are stored inline so setting them does not allocate
+/// any other type is boxed once and then overwritten in place while it keeps the same type
+pub(crate) enum StateValue {
+    F32(f32),
+    Usize(usize),
+    Bool(bool),
+    I32(i32),
+    Boxed(Box<dyn AnimationStateObj>),
+}
+
+impl StateValue {
+    pub(crate) fn new<D: AnimationStateObj>(val: D) -> StateValue {
+        let any = &val as &dyn Any;
+        if let Some(val) = any.downcast_ref::<f32>() {
+            StateValue::F32(*val)
+        } else if let Some(val) = any.downcast_ref::<usize>() {
+            StateValue::Usize(*val)
+        } else if let Some(val) = any.downcast_ref::<bool>() {
+            StateValue::Bool(*val)
+        } else if let Some(val) = any.downcast_ref::<i32>() {
+            StateValue::I32(*val)
+        } else {
+            StateValue::Boxed(Box::new(val))
+        }
+    }
+
+    pub(crate) fn from_boxed(val: Box<dyn AnimationStateObj>) -> StateValue {
+        let any = val.as_any();
+        if let Some(val) = any.downcast_ref::<f32>() {
+            StateValue::F32(*val)
+        } else if let Some(val) = any.downcast_ref::<usize>() {
+            StateValue::Usize(*val)
+        } else if let Some(val) = any.downcast_ref::<bool>() {
+            StateValue::Bool(*val)
+        } else if let Some(val) = any.downcast_ref::<i32>() {
+            StateValue::I32(*val)
+        } else {
+            StateValue::Boxed(val)
+        }
+    }
+
+    /// replaces the value, reusing the box if the new value is the same type
+    pub(crate) fn set<D: AnimationStateObj>(&mut self, val: D) {
+        if let Some(current) = self.as_obj_mut().as_any_mut().downcast_mut::<D>() {
+            *current = val;
+        } else {
+            *self = StateValue::new(val);
+        }
+    }
+
+    pub(crate) fn as_obj(&self) -> &dyn AnimationStateObj {
+        match self {
+            StateValue::F32(val) => val,
+            StateValue::Usize(val) => val,
+            StateValue::Bool(val) => val,
+            StateValue::I32(val) => val,
+            StateValue::Boxed(val) => val.as_ref(),
+        }
+    }
+
+    pub(crate) fn as_obj_mut(&mut self) -> &mut dyn AnimationStateObj {
+        match self {
+            StateValue::F32(val) => val,
+            StateValue::Usize(val) => val,
+            StateValue::Bool(val) => val,
+            StateValue::I32(val) => val,
+            StateValue::Boxed(val) => val.as_mut(),
+        }
+    }
+
+    pub(crate) fn downcast_ref<D: 'static>(&self) -> Option<&D> {
+        self.as_obj().as_any().downcast_ref()
+    }
+}
+
+impl Clone for StateValue {
+    fn clone(&self) -> Self {
+        match self {
+            StateValue::F32(val) => StateValue::F32(*val),
+            StateValue::Usize(val) => StateValue::Usize(*val),
+            StateValue::Bool(val) => StateValue::Bool(*val),
+            StateValue::I32(val) => StateValue::I32(*val),
+            StateValue::Boxed(val) => StateValue::Boxed(val.clone_obj()),
+        }
+    }
+}
+
+impl std::fmt::Debug for StateValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_obj().fmt(f)
+    }
+}
+
+/// The value of an attribute, if it was changed this frame and how long it is kept if it is temporary
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Slot {
+    pub(crate) value: Option<StateValue>,
+    pub(crate) changed: bool,
+    pub(crate) lifetime: Option<Lifetime>,
+    /// seconds and frames since the value was last set, only counted if it has a lifetime
+    pub(crate) idle: (f32, usize),
+}
+
+impl Slot {
+    /// counts one more frame of `delta` without the value being set and clears it if its lifetime is over
+    pub(crate) fn expire(&mut self, delta: f32) {
+        let Some(lifetime) = self.lifetime else {return;};
+        if self.changed {
+            self.idle = (0.0, 0);
+            return;
+        }
+        self.idle.0 += delta;
+        self.idle.1 += 1;
+        if lifetime.expired(self.idle.0, self.idle.1) {
+            self.value = None;
+        }
+    }
+}
+
+/// The core attributes in the order of there slots
+static CORE: [Attribute; CORE_SLOTS] = [
+    Attribute::Default,
+    Attribute::Delta,
+    Attribute::Frames,
+    Attribute::TimeThisFrame,
+    Attribute::FlipX,
+    Attribute::FlipY,
+    Attribute::LastFPS,
+    Attribute::Color,
+    Attribute::Alpha,
+    Attribute::Anchor,
+    Attribute::CustomSize,
+];
+
+const CORE_SLOTS: usize = 11;
+
+/// The slots of an [`AnimationState`](crate::state::AnimationState)
+/// core attributes have a fixed slot so they are found without hashing
+/// custom attributes and indexes are kept sorted by there id and found with a binary search
+#[derive(Clone, Default)]
+pub(crate) struct Slots {
+    core: [Slot; CORE_SLOTS],
+    custom: Vec<(Attribute, Slot)>,
+}
+
+fn core_slot(attribute: &Attribute) -> Option<usize> {
+    Some(match attribute {
+        Attribute::Default => 0,
+        Attribute::Delta => 1,
+        Attribute::Frames => 2,
+        Attribute::TimeThisFrame => 3,
+        Attribute::FlipX => 4,
+        Attribute::FlipY => 5,
+        Attribute::LastFPS => 6,
+        Attribute::Color => 7,
+        Attribute::Alpha => 8,
+        Attribute::Anchor => 9,
+        Attribute::CustomSize => 10,
+        _ => return None,
+    })
+}
+
+/// the key custom slots are sorted by, indexes and custom attributes with the same id are diffrent slots
+fn custom_key(attribute: &Attribute) -> (bool, u64) {
+    (attribute.is_index(), attribute.get_id().unwrap_or_default())
+}
+
+impl Slots {
+    pub(crate) fn get(&self, attribute: &Attribute) -> Option<&Slot> {
+        match core_slot(attribute) {
+            Some(index) => Some(&self.core[index]),
+            None => {
+                let key = custom_key(attribute);
+                let index = self
+                    .custom
+                    .binary_search_by_key(&key, |(attribute, _)| custom_key(attribute))
+                    .ok()?;
+                Some(&self.custom[index].1)
+            }
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, attribute: &Attribute) -> Option<&mut Slot> {
+        match core_slot(attribute) {
+            Some(index) => Some(&mut self.core[index]),
+            None => {
+                let key = custom_key(attribute);
+                let index = self
+                    .custom
+                    .binary_search_by_key(&key, |(attribute, _)| custom_key(attribute))
+                    .ok()?;
+                Some(&mut self.custom[index].1)
+            }
+        }
+    }
+
+    /// the slot for `attribute`, it is added if this state does not have it yet
+    pub(crate) fn entry(&mut self, attribute: Attribute) -> &mut Slot {
+        if let Some(index) = core_slot(&attribute) {
+            return &mut self.core[index];
+        }
+        let key = custom_key(&attribute);
+        let index = match self
+            .custom
+            .binary_search_by_key(&key, |(attribute, _)| custom_key(attribute))
+        {
+            Ok(index) => index,
+            Err(index) => {
+                self.custom.insert(index, (attribute, Slot::default()));
+                index
+            }
+        };
+        &mut self.custom[index].1
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&Attribute, &Slot)> {
+        CORE.iter().zip(self.core.iter()).chain(
+            self.custom
+                .iter()
+                .map(|(attribute, slot)| (attribute, slot)),
+        )
+    }
+
+    /// every attribute that has a value
+    pub(crate) fn values(&self) -> impl Iterator<Item = (&Attribute, &StateValue)> {
+        self.iter()
+            .filter_map(|(attribute, slot)| Some((attribute, slot.value.as_ref()?)))
+    }
+
+    pub(crate) fn changed(&self) -> impl Iterator<Item = &Attribute> {
+        self.iter()
+            .filter(|(_, slot)| slot.changed)
+            .map(|(attribute, _)| attribute)
+    }
+
+    /// the attributes with a lifetime of one frame
+    pub(crate) fn temporary(&self) -> impl Iterator<Item = &Attribute> {
+        self.iter()
+            .filter(|(_, slot)| slot.lifetime == Some(Lifetime::FRAME))
+            .map(|(attribute, _)| attribute)
+    }
+
+    /// the attributes with any lifetime other then one frame
+    pub(crate) fn lifetimes(&self) -> impl Iterator<Item = (&Attribute, Lifetime)> {
+        self.iter()
+            .filter_map(|(attribute, slot)| match slot.lifetime {
+                Some(lifetime) if lifetime != Lifetime::FRAME => Some((attribute, lifetime)),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn mark_changed(&mut self, attributes: impl IntoIterator<Item = Attribute>) {
+        for attribute in attributes {
+            self.entry(attribute).changed = true;
+        }
+    }
+
+    pub(crate) fn mark_temporary(&mut self, attributes: impl IntoIterator<Item = Attribute>) {
+        for attribute in attributes {
+            self.entry(attribute).lifetime = Some(Lifetime::FRAME);
+        }
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut Slot> {
+        self.core
+            .iter_mut()
+            .chain(self.custom.iter_mut().map(|(_, slot)| slot))
+    }
+}
+
+impl std::fmt::Debug for Slots {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.values()).finish()
+    }
+}
+
+#[test]
+fn slots_reuse_values() {
+    let speed = Attribute::new_attribute("SlotSpeed");
+    let fall = Attribute::new_index("SlotFall");
+    let mut slots = Slots::default();
+    slots.entry(fall.clone()).value = Some(StateValue::new(2usize));
+    slots.entry(speed.clone()).value = Some(StateValue::new(1.0f32));
+    slots.entry(Attribute::Color).value = Some(StateValue::new(bevy::prelude::Color::RED));
+    let color = slots
+        .get_mut(&Attribute::Color)
+        .unwrap()
+        .value
+        .as_mut()
+        .unwrap();
+    assert!(matches!(color, StateValue::Boxed(_)));
+    color.set(bevy::prelude::Color::BLUE);
+    assert_eq!(color.downcast_ref(), Some(&bevy::prelude::Color::BLUE));
+    let speed_slot = slots.get(&speed).unwrap().value.as_ref().unwrap();
+    assert!(matches!(speed_slot, StateValue::F32(_)));
+    assert_eq!(
+        slots
+            .get(&fall)
+            .unwrap()
+            .value
+            .as_ref()
+            .unwrap()
+            .downcast_ref(),
+        Some(&2usize)
+    );
+    assert!(slots.get(&Attribute::new_attribute("SlotFall")).is_none());
+    assert_eq!(slots.values().count(), 3);
+}
@@ -1,55 +1,429 @@
 use crate::error::StateError;
+use crate::events::{AnimationEvent, FrameChanged};
+use crate::hitbox::Hitbox;
+use crate::nodes::frame_data::SyncProgress;
+use crate::playback::{AnimationPlayback, CustomClockDelta, TimeSource};
+use crate::slots::{Slots, StateValue};
+use crate::target::AnimationOutput;
+use crate::timers::{AttributeTimer, Lifetime};
 
 use super::prelude::*;
-use bevy::utils::{HashMap, HashSet};
+use bevy::utils::HashMap;
 
+use bevy::asset::HandleId;
 use bevy::prelude::*;
+use bevy::reflect::serde::{ReflectSerializer, UntypedReflectDeserializer};
+use bevy::reflect::{FromType, GetTypeRegistration, TypeRegistryInternal};
 
 use std::any::Any;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
 
 pub trait AnimationStateObj: Any + Send + Sync + Reflect {
+    /// the registration of this type with [`ReflectAnimationStateObj`] added
     fn get_registration(&self) -> bevy::reflect::TypeRegistration;
+    /// a copy of this value
+    fn clone_obj(&self) -> Box<dyn AnimationStateObj>;
 }
 
-impl<T: Any + Send + Sync + Reflect + bevy::reflect::GetTypeRegistration> AnimationStateObj for T {
+impl<T: Any + Send + Sync + FromReflect + GetTypeRegistration> AnimationStateObj for T {
     fn get_registration(&self) -> bevy::reflect::TypeRegistration {
-        T::get_type_registration()
+        let mut registration = T::get_type_registration();
+        registration.insert(<ReflectAnimationStateObj as FromType<T>>::from_type());
+        registration
     }
+
+    fn clone_obj(&self) -> Box<dyn AnimationStateObj> {
+        Box::new(T::from_reflect(self.as_reflect()).expect("a value can be made from itself"))
+    }
+}
+
+impl std::fmt::Debug for dyn AnimationStateObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_reflect().debug(f)
+    }
+}
+
+/// Makes a value that can be stored in an [`AnimationState`] out of a reflected value
+/// this is needed to deserialize an `AnimationState`, add it with `#[reflect(AnimationStateObj)]`
+/// it is added to the core types by [`SpriteAnimationPlugin`]
+#[derive(Clone)]
+pub struct ReflectAnimationStateObj {
+    from_reflect: fn(&dyn Reflect) -> Option<Box<dyn AnimationStateObj>>,
+}
+
+impl ReflectAnimationStateObj {
+    pub fn from_reflect(&self, value: &dyn Reflect) -> Option<Box<dyn AnimationStateObj>> {
+        (self.from_reflect)(value)
+    }
+}
+
+impl<T: AnimationStateObj + FromReflect> FromType<T> for ReflectAnimationStateObj {
+    fn from_type() -> Self {
+        ReflectAnimationStateObj {
+            from_reflect: |value| {
+                T::from_reflect(value).map(|value| Box::new(value) as Box<dyn AnimationStateObj>)
+            },
+        }
+    }
+}
+
+/// The value types of the core attributes, these can be deserialized without an `AppTypeRegistry`
+fn core_state_types() -> &'static TypeRegistryInternal {
+    static CORE_STATE_TYPES: OnceLock<TypeRegistryInternal> = OnceLock::new();
+    CORE_STATE_TYPES.get_or_init(|| {
+        let mut registry = TypeRegistryInternal::new();
+        register_core_state_types(&mut registry);
+        registry
+    })
+}
+
+/// The `AppTypeRegistry` of the last app the plugin was added to
+/// plain `Deserialize` (and so a `DynamicScene`) has no registry of its own to look value types up in
+static APP_STATE_TYPES: std::sync::RwLock<Option<AppTypeRegistry>> = std::sync::RwLock::new(None);
+
+/// makes plain `Deserialize` look value types up in `registry`
+pub(crate) fn use_app_state_types(registry: &AppTypeRegistry) {
+    *APP_STATE_TYPES.write().unwrap() = Some(registry.clone());
 }
 
-#[derive(Component)]
+/// registers the value types of the core attributes with [`ReflectAnimationStateObj`]
+pub(crate) fn register_core_state_types(registry: &mut TypeRegistryInternal) {
+    register_state_type::<f32>(registry);
+    register_state_type::<bool>(registry);
+    register_state_type::<usize>(registry);
+    register_state_type::<i32>(registry);
+    register_state_type::<Color>(registry);
+    register_state_type::<Vec2>(registry);
+    register_state_type::<bevy::sprite::Anchor>(registry);
+}
+
+/// registers `T` and adds [`ReflectAnimationStateObj`] to its registration
+pub(crate) fn register_state_type<T: FromReflect + GetTypeRegistration>(
+    registry: &mut TypeRegistryInternal,
+) {
+    registry.register::<T>();
+    registry.register_type_data::<T, ReflectAnimationStateObj>();
+}
+
+#[derive(Component, Reflect)]
+#[reflect_value(Component, Serialize, Deserialize)]
 pub struct AnimationState {
-    data: HashMap<Attribute, Box<dyn AnimationStateObj>>,
-    pub(crate) changed: HashSet<Attribute>,
-    pub(crate) temp: HashSet<Attribute>,
+    slots: Slots,
+    timers: Vec<AttributeTimer>,
+    /// splitmix64 state of the random numbers used by nodes, `None` until it is seeded
+    rng: Option<u64>,
+    pub(crate) markers: Vec<(NodeId<'static>, Cow<'static, str>, usize)>,
+    pub(crate) visited: Vec<HandleId>,
+    pub(crate) call_stack: Vec<NodeId<'static>>,
+    pub(crate) output: Option<AnimationOutput>,
+    /// the last clip to run in each sync group and its normalized progress
+    pub(crate) sync: HashMap<u64, SyncProgress>,
+    pub(crate) root_motion: (Vec2, f32),
+    pub(crate) hitboxes: Vec<Hitbox>,
+    pub(crate) frame_changed: bool,
 }
 
 impl std::fmt::Debug for AnimationState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AnimationState")
-            // .field("data", &self.data)
-            .field("changed", &self.changed)
-            .field("temp", &self.temp)
+            .field("data", &self.slots)
+            .field("changed", &self.slots.changed().collect::<Vec<_>>())
+            .field("temp", &self.slots.temporary().collect::<Vec<_>>())
+            .field("lifetimes", &self.slots.lifetimes().collect::<Vec<_>>())
+            .field("timers", &self.timers)
+            .field("markers", &self.markers)
+            .field("frame_changed", &self.frame_changed)
             .finish()
     }
 }
 
+impl Clone for AnimationState {
+    fn clone(&self) -> Self {
+        AnimationState {
+            slots: self.slots.clone(),
+            timers: self.timers.clone(),
+            rng: self.rng,
+            markers: self.markers.clone(),
+            visited: self.visited.clone(),
+            call_stack: self.call_stack.clone(),
+            output: self.output.clone(),
+            sync: self.sync.clone(),
+            root_motion: self.root_motion,
+            hitboxes: self.hitboxes.clone(),
+            frame_changed: self.frame_changed,
+        }
+    }
+}
+
+/// Only the attribute values, the temporary attributes and the changed attributes are saved
+/// each value is saved with its type name so the type must be in the `AppTypeRegistry`
+impl serde::Serialize for AnimationState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AnimationState", 6)?;
+        state.serialize_field("data", &StateData(&self.slots))?;
+        state.serialize_field("temp", &self.slots.temporary().collect::<Vec<_>>())?;
+        state.serialize_field("changed", &self.slots.changed().collect::<Vec<_>>())?;
+        state.serialize_field(
+            "lifetimes",
+            &self.slots.lifetimes().collect::<BTreeMap<_, _>>(),
+        )?;
+        state.serialize_field("timers", &self.timers)?;
+        state.serialize_field("rng", &self.rng)?;
+        state.end()
+    }
+}
+
+struct StateData<'a>(&'a Slots);
+
+impl serde::Serialize for StateData<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(None)?;
+        for (key, val) in self.0.values() {
+            map.serialize_entry(key, &StateValueSerializer(val.as_obj()))?;
+        }
+        map.end()
+    }
+}
+
+/// Writes a value with the `ReflectSerialize` of its own registration
+/// in the same form as a `ReflectSerializer` so it is read back with an `UntypedReflectDeserializer`
+struct StateValueSerializer<'a>(&'a dyn AnimationStateObj);
+
+impl serde::Serialize for StateValueSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let registration = self.0.get_registration();
+        let Some(serialize) = registration.data::<ReflectSerialize>() else {
+            // core types like Anchor only derive Reflect so they are written field by field
+            return serde::Serialize::serialize(
+                &ReflectSerializer::new(self.0.as_reflect(), core_state_types()),
+                serializer,
+            );
+        };
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(
+            registration.type_name(),
+            serialize.get_serializable(self.0.as_reflect()).borrow(),
+        )?;
+        map.end()
+    }
+}
+
+/// Looks value types up in the `AppTypeRegistry` of the app the plugin was added to,
+/// only values of the core attribute types can be read before the plugin is added
+/// use [`AnimationStateDeserializer`] to read with a registry of your choice
+impl<'de> serde::Deserialize<'de> for AnimationState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::DeserializeSeed;
+        let app_types = APP_STATE_TYPES.read().unwrap().clone();
+        let Some(app_types) = app_types else {return AnimationStateDeserializer::new(core_state_types()).deserialize(deserializer);};
+        // scenes are read while their loader holds the registry
+        let registry = app_types.0.internal.read_recursive();
+        AnimationStateDeserializer::new(&registry).deserialize(deserializer)
+    }
+}
+
+/// Reads an [`AnimationState`] looking up the types of its values in `registry`
+/// the types must be registered with [`ReflectAnimationStateObj`], [`SpriteAnimationPlugin`] does this for the core types
+pub struct AnimationStateDeserializer<'a> {
+    registry: &'a TypeRegistryInternal,
+}
+
+impl<'a> AnimationStateDeserializer<'a> {
+    pub fn new(registry: &'a TypeRegistryInternal) -> AnimationStateDeserializer<'a> {
+        AnimationStateDeserializer { registry }
+    }
+}
+
+impl<'a, 'de> serde::de::DeserializeSeed<'de> for AnimationStateDeserializer<'a> {
+    type Value = AnimationState;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "AnimationState",
+            &["data", "temp", "changed", "lifetimes", "timers", "rng"],
+            StateVisitor {
+                registry: self.registry,
+            },
+        )
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Fileds {
+    Data,
+    Temp,
+    Changed,
+    Lifetimes,
+    Timers,
+    Rng,
+}
+
+struct StateVisitor<'a> {
+    registry: &'a TypeRegistryInternal,
+}
+
+impl<'a, 'de> serde::de::Visitor<'de> for StateVisitor<'a> {
+    type Value = AnimationState;
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("AnimationState with data, temp and changed")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        use serde::de::Error;
+        let mut state = AnimationState::empty();
+        let Some(data) = seq.next_element_seed(StateDataSeed::new(self.registry))? else {return Err(A::Error::invalid_length(0, &self));};
+        let Some(temp) = seq.next_element::<Vec<Attribute>>()? else {return Err(A::Error::invalid_length(1, &self));};
+        let Some(changed) = seq.next_element::<Vec<Attribute>>()? else {return Err(A::Error::invalid_length(2, &self));};
+        state.insert_data(data);
+        state.slots.mark_temporary(temp);
+        state.slots.mark_changed(changed);
+        if let Some(lifetimes) = seq.next_element::<Vec<(Attribute, Lifetime)>>()? {
+            for (key, lifetime) in lifetimes {
+                state.set_lifetime(key, lifetime);
+            }
+        }
+        if let Some(timers) = seq.next_element::<Vec<AttributeTimer>>()? {
+            state.timers = timers;
+        }
+        if let Some(rng) = seq.next_element::<Option<u64>>()? {
+            state.rng = rng;
+        }
+        Ok(state)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut state = AnimationState::empty();
+        while let Some(key) = map.next_key::<Fileds>()? {
+            match key {
+                Fileds::Data => {
+                    state.insert_data(map.next_value_seed(StateDataSeed::new(self.registry))?)
+                }
+                Fileds::Temp => state
+                    .slots
+                    .mark_temporary(map.next_value::<Vec<Attribute>>()?),
+                Fileds::Changed => state
+                    .slots
+                    .mark_changed(map.next_value::<Vec<Attribute>>()?),
+                Fileds::Lifetimes => {
+                    for (key, lifetime) in map.next_value::<BTreeMap<Attribute, Lifetime>>()? {
+                        state.set_lifetime(key, lifetime);
+                    }
+                }
+                Fileds::Timers => state.timers = map.next_value()?,
+                Fileds::Rng => state.rng = map.next_value()?,
+            }
+        }
+        Ok(state)
+    }
+}
+
+/// Reads a map of attributes to reflected values, the types are looked up in `registry`
+pub(crate) struct StateDataSeed<'a> {
+    registry: &'a TypeRegistryInternal,
+}
+
+impl<'a> StateDataSeed<'a> {
+    pub(crate) fn new(registry: &'a TypeRegistryInternal) -> StateDataSeed<'a> {
+        StateDataSeed { registry }
+    }
+}
+
+impl<'a, 'de> serde::de::DeserializeSeed<'de> for StateDataSeed<'a> {
+    type Value = HashMap<Attribute, Box<dyn AnimationStateObj>>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de> serde::de::Visitor<'de> for StateDataSeed<'a> {
+    type Value = HashMap<Attribute, Box<dyn AnimationStateObj>>;
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of Attribute to reflected value")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+        let mut data = HashMap::default();
+        while let Some(key) = map.next_key::<Attribute>()? {
+            let val = map.next_value_seed(UntypedReflectDeserializer::new(self.registry))?;
+            let Some(info) = val.get_represented_type_info() else {return Err(A::Error::custom(format!("{} has no type info", val.type_name())));};
+            let Some(from_reflect) = self.registry.get_type_data::<ReflectAnimationStateObj>(info.type_id()) else {return Err(A::Error::custom(format!("{} dose not #[reflect(AnimationStateObj)]", info.type_name())));};
+            let Some(val) = from_reflect.from_reflect(val.as_ref()) else {return Err(A::Error::custom(format!("could not make {} from reflect", info.type_name())));};
+            data.insert(key, val);
+        }
+        Ok(data)
+    }
+}
+
 impl Default for AnimationState {
     fn default() -> Self {
-        let mut data: HashMap<Attribute, Box<dyn AnimationStateObj>> = HashMap::default();
-        data.insert(Attribute::Delta, Box::new(0.0f32));
-        data.insert(Attribute::Frames, Box::new(0));
-        data.insert(Attribute::FlipX, Box::new(false));
-        data.insert(Attribute::FlipY, Box::new(false));
-        let s = Self {
-            data,
-            changed: HashSet::new(),
-            temp: HashSet::new(),
-        };
+        let mut s = AnimationState::empty();
+        s.slots.entry(Attribute::Delta).value = Some(StateValue::new(0.0f32));
+        s.slots.entry(Attribute::Frames).value = Some(StateValue::new(0));
+        s.slots.entry(Attribute::FlipX).value = Some(StateValue::new(false));
+        s.slots.entry(Attribute::FlipY).value = Some(StateValue::new(false));
         s
     }
 }
 
+impl AnimationState {
+    /// a state with no attributes set
+    fn empty() -> AnimationState {
+        AnimationState {
+            slots: Slots::default(),
+            timers: Vec::new(),
+            rng: None,
+            markers: Vec::new(),
+            visited: Vec::new(),
+            call_stack: Vec::new(),
+            output: None,
+            sync: HashMap::default(),
+            root_motion: (Vec2::ZERO, 0.0),
+            hitboxes: Vec::new(),
+            frame_changed: false,
+        }
+    }
+
+    /// sets the values read by a [`StateDataSeed`] without marking them changed
+    fn insert_data(&mut self, data: HashMap<Attribute, Box<dyn AnimationStateObj>>) {
+        for (key, val) in data {
+            self.slots.entry(key).value = Some(StateValue::from_boxed(val));
+        }
+    }
+}
+
 impl AnimationState {
     /// will return `D` for attribute panics if the attribute is not set
     /// or `D` is the wrong type
@@ -66,10 +440,8 @@ impl AnimationState {
     /// * NotFound - there is no data set for the Attribute
     #[inline(always)]
     pub fn get_attribute<D: 'static>(&self, key: &Attribute) -> Result<&D, StateError> {
-        if let Some(data) = self.data.get(key) {
-            data.as_any()
-                .downcast_ref::<D>()
-                .ok_or(StateError::WrongType)
+        if let Some(data) = self.slots.get(key).and_then(|slot| slot.value.as_ref()) {
+            data.downcast_ref::<D>().ok_or(StateError::WrongType)
         } else {
             Err(StateError::NotFound)
         }
@@ -77,33 +449,204 @@ impl AnimationState {
 
     /// sets an Attribute to a specific type and val
     pub fn set_attribute<D: AnimationStateObj>(&mut self, key: Attribute, val: D) {
-        self.change(key.clone());
-        self.data.insert(key, Box::new(val));
+        let slot = self.slots.entry(key);
+        slot.changed = true;
+        match &mut slot.value {
+            Some(current) => current.set(val),
+            None => slot.value = Some(StateValue::new(val)),
+        }
     }
 
-    /// Will stop this Attribute being cleared after a frame it is not set
+    /// gets the value of a [`TypedAttribute`], the type is taken from the key
+    /// # Errors
+    /// * WrongType - the value was set with `set_attribute` using a diffrent type
+    /// * NotFound - there is no data set for the Attribute
+    #[inline(always)]
+    pub fn get<D: 'static>(&self, key: &TypedAttribute<D>) -> Result<&D, StateError> {
+        self.get_attribute(key.attribute())
+    }
+
+    /// sets the value of a [`TypedAttribute`]
+    pub fn set<D: AnimationStateObj>(&mut self, key: &TypedAttribute<D>, val: D) {
+        self.set_attribute(key.attribute().clone(), val);
+    }
+
+    /// Will stop this Attribute being cleared when it is not set
+    /// retruns true if it had a lifetime
     pub fn set_persistent(&mut self, temp: &Attribute) -> bool {
-        self.temp.remove(temp)
+        let Some(slot) = self.slots.get_mut(temp) else {return false;};
+        slot.lifetime.take().is_some()
     }
 
     /// Will clear this Attribute if it is not set each frame
     pub fn set_temporary(&mut self, temp: Attribute) -> bool {
-        self.temp.insert(temp)
+        self.set_lifetime(temp, Lifetime::FRAME)
     }
 
-    /// retrun a bool based on if the Attribute has changed this frame
-    pub fn changed(&self, attribute: &Attribute) -> bool {
-        self.changed.contains(attribute)
+    /// Will clear this Attribute once `lifetime` has passed without it being set
+    /// retruns true if it did not have a lifetime
+    pub fn set_lifetime(&mut self, attribute: Attribute, lifetime: Lifetime) -> bool {
+        let slot = self.slots.entry(attribute);
+        slot.idle = (0.0, 0);
+        slot.lifetime.replace(lifetime).is_none()
     }
 
-    #[inline]
-    fn change(&mut self, attribute: Attribute) {
-        self.changed.insert(attribute);
+    /// sets an Attribute that is cleared once `lifetime` has passed without it being set again
+    pub fn set_attribute_for<D: AnimationStateObj>(
+        &mut self,
+        key: Attribute,
+        val: D,
+        lifetime: Lifetime,
+    ) {
+        self.set_lifetime(key.clone(), lifetime);
+        self.set_attribute(key, val);
+    }
+
+    /// the lifetime of an Attribute, `None` if it is persistent
+    pub fn lifetime(&self, attribute: &Attribute) -> Option<Lifetime> {
+        self.slots.get(attribute).and_then(|slot| slot.lifetime)
+    }
+
+    /// retrun true if the Attribute will be cleared when it is not set
+    pub fn is_temporary(&self, attribute: &Attribute) -> bool {
+        self.lifetime(attribute).is_some()
+    }
+
+    /// starts a timer, the timer attribute is set to `0.0` and counts up the seconds of [`Attribute::Delta`]
+    /// a timer that is already running on the same attribute is replaced
+    pub fn add_timer(&mut self, timer: AttributeTimer) {
+        self.remove_timer(&timer.timer);
+        self.set_attribute(timer.timer.clone(), 0.0f32);
+        self.timers.push(timer);
+    }
+
+    /// stops the timer on `timer`, the attribute keeps its last value
+    pub fn remove_timer(&mut self, timer: &Attribute) {
+        self.timers.retain(|running| &running.timer != timer);
+    }
+
+    pub fn timers(&self) -> &[AttributeTimer] {
+        &self.timers
+    }
+
+    pub(crate) fn has_timers(&self) -> bool {
+        !self.timers.is_empty()
+    }
+
+    /// adds [`Attribute::Delta`] to every timer
+    pub(crate) fn advance_timers(&mut self) {
+        let delta = self.delta();
+        let timers = std::mem::take(&mut self.timers);
+        for timer in timers.iter() {
+            let time = self
+                .get_attribute::<f32>(&timer.timer)
+                .copied()
+                .unwrap_or_default();
+            self.set_attribute(timer.timer.clone(), time + delta);
+        }
+        self.timers = timers;
+    }
+
+    /// starts the random numbers used by nodes like [`RandomNode`] from `seed`
+    /// states are seeded from their entity when they are added, seed them again to make the numbers reproducible
+    pub fn seed_random(&mut self, seed: u64) {
+        self.rng = Some(seed);
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        self.rng.is_some()
+    }
+
+    /// the next random number from this states seeded sequence
+    pub fn random_u64(&mut self) -> u64 {
+        let rng = self.rng.get_or_insert(0);
+        *rng = rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// a random number in `0.0..1.0`
+    pub fn random_f32(&mut self) -> f32 {
+        (self.random_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn delta(&self) -> f32 {
+        self.get_attribute::<f32>(&Attribute::Delta)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// retrun a bool based on if the Attribute has changed this frame
+    pub fn changed(&self, attribute: &Attribute) -> bool {
+        self.slots.get(attribute).is_some_and(|slot| slot.changed)
     }
 
     /// removes the data from an Attribute and forgets its type
     pub fn clear_attribute(&mut self, attribute: &Attribute) {
-        self.data.remove(attribute);
+        if let Some(slot) = self.slots.get_mut(attribute) {
+            slot.value = None;
+        }
+    }
+
+    /// the value of an Attribute without knowing its type
+    pub fn get_reflect(&self, key: &Attribute) -> Option<&dyn Reflect> {
+        self.slots
+            .get(key)
+            .and_then(|slot| slot.value.as_ref())
+            .map(|val| val.as_obj().as_reflect())
+    }
+
+    pub(crate) fn set_boxed(&mut self, key: Attribute, val: Box<dyn AnimationStateObj>) {
+        let slot = self.slots.entry(key);
+        slot.changed = true;
+        slot.value = Some(StateValue::from_boxed(val));
+    }
+
+    /// records that the playback of `node` reached a frame with a marker on it
+    /// this will be sent as an [`AnimationEvent`](crate::events::AnimationEvent) at the end of [`AnimationSet::Update`]
+    pub fn emit_marker(&mut self, node: NodeId<'static>, marker: Cow<'static, str>, frame: usize) {
+        self.markers.push((node, marker, frame));
+    }
+
+    /// adds to the distance the entity will be moved by at the end of [`AnimationSet::Update`]
+    pub fn add_root_motion(&mut self, offset: Vec2, rotation: f32) {
+        self.root_motion.0 += offset;
+        self.root_motion.1 += rotation;
+    }
+
+    /// the offset and rotation the frames reached this frame add up to
+    pub fn root_motion(&self) -> (Vec2, f32) {
+        self.root_motion
+    }
+
+    /// the hitboxes of the frame that is showing, these are not flipped
+    pub fn hitboxes(&self) -> &[Hitbox] {
+        &self.hitboxes
+    }
+
+    /// the markers that have been reached this frame as `(node, marker, frame)`
+    pub fn markers(&self) -> impl Iterator<Item = (&NodeId<'static>, &str, usize)> {
+        self.markers
+            .iter()
+            .map(|(node, marker, frame)| (node, marker.as_ref(), *frame))
+    }
+
+    /// the nodes that were run the last time this state was updated in the order they were run
+    pub fn visited(&self) -> impl Iterator<Item = NodeId<'static>> + '_ {
+        self.visited.iter().map(|id| handle_to_node(*id))
+    }
+
+    /// the frame the nodes picked the last time this state was updated
+    /// this is `None` if the nodes did not get to a frame
+    pub fn output(&self) -> Option<&AnimationOutput> {
+        self.output.as_ref()
+    }
+
+    /// retrun true if the image or atlas index shown by this entity was changed this frame
+    pub fn frame_changed(&self) -> bool {
+        self.frame_changed
     }
 
     /// get the usize for an index panics if given something other then Index or IndexId
@@ -126,13 +669,42 @@ impl AnimationState {
         )
     }
 
+    /// clears the temporary attributes whose lifetime ran out this frame
+    /// and resets the timers whose watched attribute changed
+    pub(crate) fn clear_unchanged_temp(&mut self) {
+        let delta = self.delta();
+        for slot in self.slots.iter_mut() {
+            slot.expire(delta);
+        }
+        let timers = std::mem::take(&mut self.timers);
+        for timer in timers.iter() {
+            let Some(watched) = &timer.watched else {continue;};
+            if self.changed(watched) {
+                self.set_attribute(timer.timer.clone(), 0.0f32);
+            }
+        }
+        self.timers = timers;
+    }
+
+    /// forgets what was changed last frame
+    pub(crate) fn clear_changed(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.changed = false;
+        }
+        self.markers.clear();
+        self.sync.retain(|_, sync| std::mem::take(&mut sync.ran));
+        self.frame_changed = false;
+        self.root_motion = (Vec2::ZERO, 0.0);
+    }
+
     #[cfg(feature = "ron")]
     pub(crate) fn set_from_ron(
         &mut self,
         attribute: &Attribute,
         s: &str,
     ) -> Result<(), StateError> {
-        let Some(main) = self.data.get_mut(attribute) else {return Err(StateError::NotFound);};
+        let Some(main) = self.slots.get_mut(attribute).and_then(|slot| slot.value.as_mut()) else {return Err(StateError::NotFound);};
+        let main = main.as_obj_mut();
         let data = main.get_registration();
         let Some(data) = data.data::<ReflectDeserialize>() else {return Err(StateError::NotRegistered(data.type_name()));};
         let mut deserializer = ron::Deserializer::from_str(s).expect("Ron To Work");
@@ -142,36 +714,265 @@ impl AnimationState {
     }
 }
 
-pub(crate) fn update_delta(time: Res<Time>, mut states: Query<&mut AnimationState>) {
-    for mut state in states.iter_mut() {
-        state.set_attribute(Attribute::Delta, time.delta_seconds());
+pub(crate) fn update_delta(
+    time: Res<Time>,
+    custom: Option<Res<CustomClockDelta>>,
+    mut states: Query<(&mut AnimationState, Option<&AnimationPlayback>)>,
+    mut warned: Local<bool>,
+) {
+    let custom = custom.map(|custom| custom.0);
+    for (mut state, playback) in states.iter_mut() {
+        let Some(playback) = playback else {
+            state.set_attribute(Attribute::Delta, time.delta_seconds());
+            continue;
+        };
+        let delta = match playback.time_source {
+            TimeSource::Scaled => time.delta_seconds(),
+            TimeSource::Unscaled => time.raw_delta_seconds(),
+            TimeSource::Custom => {
+                let Some(custom) = custom else {
+                    if !*warned {
+                        warn!("TimeSource::Custom is used but no AnimationClockPlugin was added");
+                        *warned = true;
+                    }
+                    state.set_attribute(Attribute::Delta, 0.0f32);
+                    continue;
+                };
+                custom
+            }
+        };
+        state.set_attribute(Attribute::Delta, playback.scale(delta));
     }
 }
 
 pub(crate) fn clear_unchanged_temp(mut states: Query<&mut AnimationState>) {
     for mut state in states.iter_mut() {
-        let state = state.as_mut();
-        let mut to_clear = Vec::with_capacity(state.temp.len());
-        for temp in state.temp.iter() {
-            if !state.changed(temp) {
-                to_clear.push(temp.clone());
-            }
-        }
-        for clear in to_clear.iter() {
-            state.clear_attribute(clear)
+        state.clear_unchanged_temp();
+    }
+}
+
+/// seeds the random numbers of new states from there entity so entities do not all make the same choices
+pub(crate) fn seed_new_states(
+    mut states: Query<(Entity, &mut AnimationState), Added<AnimationState>>,
+) {
+    for (entity, mut state) in states.iter_mut() {
+        if !state.is_seeded() {
+            state.seed_random(entity.to_bits());
         }
     }
 }
 
 pub(crate) fn clear_changed(mut states: Query<&mut AnimationState>) {
     for mut state in states.iter_mut() {
-        state.changed.clear();
+        state.clear_changed();
     }
 }
 
-pub(crate) fn flip_update(mut sprites: Query<(&AnimationState, &mut Sprite)>) {
+pub(crate) fn flip_update(
+    mut sprites: Query<(&AnimationState, &mut Sprite)>,
+    mut atlas_sprites: Query<(&AnimationState, &mut TextureAtlasSprite)>,
+) {
     for (state, mut sprite) in sprites.iter_mut() {
         sprite.flip_x = *state.attribute(&Attribute::FlipX);
         sprite.flip_y = *state.attribute(&Attribute::FlipY);
     }
+    for (state, mut sprite) in atlas_sprites.iter_mut() {
+        sprite.flip_x = *state.attribute(&Attribute::FlipX);
+        sprite.flip_y = *state.attribute(&Attribute::FlipY);
+    }
+}
+
+/// Sets the color, anchor and size of sprites from there `AnimationState`
+/// any of these attributes that are not set are left as they are
+pub(crate) fn sprite_update(
+    mut sprites: Query<(&AnimationState, &mut Sprite)>,
+    mut atlas_sprites: Query<(&AnimationState, &mut TextureAtlasSprite)>,
+) {
+    for (state, mut sprite) in sprites.iter_mut() {
+        let inner = sprite.bypass_change_detection();
+        if apply_sprite_attributes(
+            state,
+            &mut inner.color,
+            &mut inner.anchor,
+            &mut inner.custom_size,
+        ) {
+            sprite.set_changed();
+        }
+    }
+    for (state, mut sprite) in atlas_sprites.iter_mut() {
+        let inner = sprite.bypass_change_detection();
+        if apply_sprite_attributes(
+            state,
+            &mut inner.color,
+            &mut inner.anchor,
+            &mut inner.custom_size,
+        ) {
+            sprite.set_changed();
+        }
+    }
+}
+
+/// returns true if anything was changed
+fn apply_sprite_attributes(
+    state: &AnimationState,
+    color: &mut Color,
+    anchor: &mut bevy::sprite::Anchor,
+    custom_size: &mut Option<Vec2>,
+) -> bool {
+    let mut changed = false;
+    // the alpha goes on top of the color so the sprite is compared with both applied
+    let mut target = state
+        .get_attribute::<Color>(&Attribute::Color)
+        .copied()
+        .unwrap_or(*color);
+    if let Ok(alpha) = state.get_attribute::<f32>(&Attribute::Alpha) {
+        target.set_a(*alpha);
+    }
+    if *color != target {
+        *color = target;
+        changed = true;
+    }
+    if let Ok(new) = state.get_attribute::<bevy::sprite::Anchor>(&Attribute::Anchor) {
+        if anchor.as_vec() != new.as_vec() {
+            *anchor = new.clone();
+            changed = true;
+        }
+    }
+    if let Ok(new) = state.get_attribute::<Vec2>(&Attribute::CustomSize) {
+        if *custom_size != Some(*new) {
+            *custom_size = Some(*new);
+            changed = true;
+        }
+    }
+    changed
+}
+
+pub(crate) fn send_animation_events(
+    states: Query<(Entity, &AnimationState)>,
+    mut events: EventWriter<AnimationEvent>,
+    mut frame_events: EventWriter<FrameChanged>,
+) {
+    for (entity, state) in states.iter() {
+        if state.frame_changed {
+            frame_events.send(FrameChanged { entity });
+        }
+        for (node, marker, frame) in state.markers.iter() {
+            events.send(AnimationEvent {
+                entity,
+                node: node.clone(),
+                marker: marker.clone(),
+                frame: *frame,
+            });
+        }
+    }
+}
+
+#[test]
+fn unset_sprite_attributes() {
+    let mut sprite = Sprite::default();
+    let mut state = AnimationState::default();
+    assert!(!apply_sprite_attributes(
+        &state,
+        &mut sprite.color,
+        &mut sprite.anchor,
+        &mut sprite.custom_size
+    ));
+    assert_eq!(sprite.custom_size, None);
+    state.set_attribute(Attribute::Color, Color::RED);
+    state.set_attribute(Attribute::Alpha, 0.5f32);
+    assert!(apply_sprite_attributes(
+        &state,
+        &mut sprite.color,
+        &mut sprite.anchor,
+        &mut sprite.custom_size
+    ));
+    assert_eq!(sprite.color, Color::rgba(1.0, 0.0, 0.0, 0.5));
+    assert!(!apply_sprite_attributes(
+        &state,
+        &mut sprite.color,
+        &mut sprite.anchor,
+        &mut sprite.custom_size
+    ));
+    assert_eq!(sprite.color, Color::rgba(1.0, 0.0, 0.0, 0.5));
+}
+
+#[test]
+fn state_round_trip() {
+    let health = Attribute::new_attribute("Health");
+    let mut state = AnimationState::default();
+    state.set_attribute(health.deep_clone(), 3usize);
+    state.set_temporary(health.deep_clone());
+    state.clear_changed();
+    state.set_attribute(Attribute::FlipX, true);
+    let saved = ron::to_string(&state).unwrap();
+    let loaded: AnimationState = ron::from_str(&saved).unwrap();
+    assert_eq!(*loaded.attribute::<usize>(&health), 3);
+    assert!(*loaded.attribute::<bool>(&Attribute::FlipX));
+    assert!(loaded.changed(&Attribute::FlipX));
+    assert!(!loaded.changed(&health));
+    assert!(loaded.is_temporary(&health));
+    assert!(format!("{:?}", loaded).contains("FlipX: true"));
+
+    // types that are not core attribute types need the registry they were registered in
+    let title = Attribute::new_attribute("Title");
+    state.set_attribute(title.deep_clone(), String::from("Zombie"));
+    let saved = ron::to_string(&state).unwrap();
+    assert!(ron::from_str::<AnimationState>(&saved).is_err());
+    let mut registry = TypeRegistryInternal::new();
+    register_core_state_types(&mut registry);
+    register_state_type::<String>(&mut registry);
+    let loaded = serde::de::DeserializeSeed::deserialize(
+        AnimationStateDeserializer::new(&registry),
+        &mut ron::Deserializer::from_str(&saved).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(loaded.attribute::<String>(&title), "Zombie");
+    assert_eq!(*loaded.attribute::<usize>(&health), 3);
+}
+
+#[test]
+fn state_scene_round_trip() {
+    use bevy::scene::serde::SceneDeserializer;
+    use serde::de::DeserializeSeed;
+
+    #[derive(Reflect, Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+    #[reflect(AnimationStateObj, Serialize, Deserialize)]
+    enum Mood {
+        Calm,
+        Angry,
+    }
+
+    let registry = AppTypeRegistry::default();
+    {
+        let mut types = registry.write();
+        register_core_state_types(&mut types);
+        types.register::<AnimationState>();
+        types.register::<Mood>();
+    }
+    use_app_state_types(&registry);
+    let mood = Attribute::new_attribute("Mood");
+    let mut state = AnimationState::default();
+    state.set_attribute(mood.deep_clone(), Mood::Calm);
+    state.set_attribute(mood.deep_clone(), Mood::Angry);
+    state.set_attribute(Attribute::FlipX, true);
+    let mut world = World::new();
+    world.insert_resource(registry.clone());
+    world.spawn(state);
+    let saved = DynamicScene::from_world(&world)
+        .serialize_ron(&registry)
+        .unwrap();
+
+    let scene = SceneDeserializer {
+        type_registry: &registry.read(),
+    }
+    .deserialize(&mut ron::Deserializer::from_str(&saved).unwrap())
+    .unwrap();
+    let mut world = World::new();
+    world.insert_resource(registry);
+    scene
+        .write_to_world(&mut world, &mut bevy::ecs::entity::EntityMap::default())
+        .unwrap();
+    let state = world.query::<&AnimationState>().single(&world);
+    assert_eq!(*state.attribute::<Mood>(&mood), Mood::Angry);
+    assert!(*state.attribute::<bool>(&Attribute::FlipX));
 }
@@ -0,0 +1,102 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// The frame the nodes picked for an entity the last time it was updated
+#[derive(Debug, Clone)]
+pub enum AnimationOutput {
+    Image(Handle<Image>),
+    Atlas(Handle<TextureAtlas>, usize),
+}
+
+/// A component that can show the frames picked by the nodes
+/// `Handle<Image>` and `TextureAtlasSprite` are set by the animation system directly,
+/// anything else needs to implement this and add [`AnimationTargetPlugin`]
+pub trait AnimationTarget: Component {
+    /// show `image`, return true if the image was changed
+    fn set_image(&mut self, image: &Handle<Image>) -> bool {
+        let _ = image;
+        false
+    }
+
+    /// show sprite `index` of `atlas`, return true if the frame was changed
+    fn set_atlas(&mut self, atlas: &Handle<TextureAtlas>, index: usize) -> bool {
+        let _ = (atlas, index);
+        false
+    }
+
+    /// called every update with the values of [`Attribute::FlipX`] and [`Attribute::FlipY`]
+    /// return true if anything was changed
+    fn set_flip(&mut self, flip_x: bool, flip_y: bool) -> bool {
+        let _ = (flip_x, flip_y);
+        false
+    }
+}
+
+impl AnimationTarget for UiImage {
+    fn set_image(&mut self, image: &Handle<Image>) -> bool {
+        if self.texture == *image {
+            return false;
+        }
+        self.texture = image.clone();
+        true
+    }
+
+    fn set_flip(&mut self, flip_x: bool, flip_y: bool) -> bool {
+        if self.flip_x == flip_x && self.flip_y == flip_y {
+            return false;
+        }
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        true
+    }
+}
+
+/// Updates `T` on every entity with an [`AnimationState`] after the nodes have been run
+/// this is added for `UiImage` by [`SpriteAnimationPlugin`]
+pub struct AnimationTargetPlugin<T: AnimationTarget>(PhantomData<T>);
+
+impl<T: AnimationTarget> Default for AnimationTargetPlugin<T> {
+    fn default() -> Self {
+        AnimationTargetPlugin(PhantomData)
+    }
+}
+
+impl<T: AnimationTarget> Plugin for AnimationTargetPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_target::<T>
+                .in_set(AnimationSet::PostUpdate)
+                .before(crate::state::send_animation_events),
+        );
+    }
+}
+
+fn update_target<T: AnimationTarget>(mut targets: Query<(&mut AnimationState, &mut T)>) {
+    for (mut state, mut target) in targets.iter_mut() {
+        let flip_x = state
+            .get_attribute::<bool>(&Attribute::FlipX)
+            .cloned()
+            .unwrap_or_default();
+        let flip_y = state
+            .get_attribute::<bool>(&Attribute::FlipY)
+            .cloned()
+            .unwrap_or_default();
+        let inner = target.bypass_change_detection();
+        let flipped = inner.set_flip(flip_x, flip_y);
+        let frame_changed = match state.output() {
+            Some(AnimationOutput::Image(image)) => inner.set_image(image),
+            Some(AnimationOutput::Atlas(atlas, index)) => inner.set_atlas(atlas, *index),
+            None => false,
+        };
+        if flipped || frame_changed {
+            target.set_changed();
+        }
+        if frame_changed {
+            state.frame_changed = true;
+        }
+    }
+}
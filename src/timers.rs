@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// How long a temporary attribute is kept after it was last set
+/// `Frames(1)` is what [`AnimationState::set_temporary`] uses, the attribute is cleared at the end of any frame it is not set
+/// ```ron
+/// // an .animator preset
+/// lifetimes: {Custom("Invulnerable"): Seconds(0.3), Index("Fall"): Frames(2)},
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, serde::Deserialize, serde::Serialize)]
+pub enum Lifetime {
+    /// cleared once this many seconds of [`Attribute::Delta`] have passed without it being set
+    Seconds(f32),
+    /// cleared at the end of the frame that is this many frames after it was last set
+    Frames(usize),
+}
+
+impl Lifetime {
+    /// the lifetime of attributes set with [`AnimationState::set_temporary`]
+    pub const FRAME: Lifetime = Lifetime::Frames(1);
+
+    /// `seconds` and `frames` are how long it has been since the attribute was last set
+    /// including the current frame
+    pub(crate) fn expired(&self, seconds: f32, frames: usize) -> bool {
+        match *self {
+            Lifetime::Seconds(lifetime) => seconds >= lifetime,
+            Lifetime::Frames(lifetime) => frames >= lifetime,
+        }
+    }
+}
+
+/// An `f32` attribute that counts up the seconds of [`Attribute::Delta`]
+/// if it watches an attribute it goes back to `0.0` at the end of every frame that attribute changed
+/// so it is the seconds since the attribute was last set
+#[derive(Debug, Clone, PartialEq, Reflect, serde::Deserialize, serde::Serialize)]
+pub struct AttributeTimer {
+    pub timer: Attribute,
+    pub watched: Option<Attribute>,
+}
+
+impl AttributeTimer {
+    pub fn new(timer: Attribute) -> AttributeTimer {
+        AttributeTimer {
+            timer,
+            watched: None,
+        }
+    }
+
+    /// a timer that is reset every time `watched` is set
+    pub fn since_changed(timer: Attribute, watched: Attribute) -> AttributeTimer {
+        AttributeTimer {
+            timer,
+            watched: Some(watched),
+        }
+    }
+}
+
+/// adds this frames delta to every timer, after [`Attribute::Delta`] is set so the nodes see the new time
+pub(crate) fn advance_timers(mut states: Query<&mut AnimationState>) {
+    for mut state in states.iter_mut() {
+        if state.has_timers() {
+            state.advance_timers();
+        }
+    }
+}
+
+#[test]
+fn lifetimes_and_timers() {
+    let flash = Attribute::new_attribute("Flash");
+    let stun = Attribute::new_index("Stun");
+    let since_hit = Attribute::new_attribute("SinceHit");
+    let hit = Attribute::new_attribute("Hit");
+    let mut state = AnimationState::default();
+    state.set_attribute_for(flash.clone(), true, Lifetime::Seconds(0.3));
+    state.set_attribute_for(stun.clone(), 1usize, Lifetime::Frames(2));
+    state.add_timer(AttributeTimer::since_changed(
+        since_hit.clone(),
+        hit.clone(),
+    ));
+    state.set_attribute(hit.clone(), true);
+    let step = |state: &mut AnimationState| {
+        state.set_attribute(Attribute::Delta, 0.2f32);
+        state.advance_timers();
+        state.clear_unchanged_temp();
+        state.clear_changed();
+    };
+    step(&mut state);
+    assert_eq!(*state.attribute::<f32>(&since_hit), 0.0);
+    step(&mut state);
+    assert!(state.get_attribute::<bool>(&flash).is_ok());
+    assert!(state.get_attribute::<usize>(&stun).is_ok());
+    assert_eq!(*state.attribute::<f32>(&since_hit), 0.2);
+    step(&mut state);
+    assert!(state.get_attribute::<bool>(&flash).is_err());
+    assert!(state.get_attribute::<usize>(&stun).is_err());
+    assert_eq!(state.lifetime(&flash), Some(Lifetime::Seconds(0.3)));
+    assert!((*state.attribute::<f32>(&since_hit) - 0.4).abs() < 0.0001);
+    state.set_attribute(hit, true);
+    step(&mut state);
+    assert_eq!(*state.attribute::<f32>(&since_hit), 0.0);
+}